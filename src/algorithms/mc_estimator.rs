@@ -80,6 +80,8 @@ impl MonteCarloEstimator {
 
     /// Estimate the canonical expectation value of the Hamiltonian.
     /// H: a function that computes the energy for a given state.
+    /// For reweighting simulation time series to other temperatures, see
+    /// `reweighting::MultiHistogram`.
     pub fn estimate_energy<F>(
         &self,
         initial_distribution: &ProbabilityDistribution,
@@ -0,0 +1,348 @@
+use crate::algorithms::wang_landau::{log_sum_exp, EnergyGrid, EnergyMoves};
+use crate::physics::potts::{square_lattice_critical_temperature, PottsModel};
+use rand::Rng;
+use std::fmt::Write as _;
+
+/// Multicanonical sampler
+/// - Samples P(E) ∝ g(E) W(E) restricted to the energy window of `grid`; moves that
+///   leave the window are rejected
+/// - `iterate` refines the weights with Berg's error-weighted recursion: for
+///   consecutive visited bins E < E', ln W(E') - ln W(E) moves by κ ln(H(E) / H(E')),
+///   with κ the share of this iteration in the statistics accumulated for that pair
+/// - Pairs never visited keep their previous weight differences, so the window is
+///   opened up gradually without the oscillations of the plain W ← W / H update
+/// - Once H(E) is flat, `production` records samples that reweight to any canonical
+///   temperature whose energy distribution lies inside the window
+pub struct Multicanonical {
+    pub grid: EnergyGrid,
+    pub ln_w: Vec<f64>,
+    pub histogram: Vec<usize>,
+    pub iteration: usize,
+    /// Accumulated statistics H(E) H(E') / (H(E) + H(E')) per visited pair.
+    statistics: Vec<f64>,
+}
+
+/// Time series of a multicanonical production run
+/// - `ln_weights[i]` is ln W(E_i) of the sample, divided out when reweighting
+pub struct MulticanonicalSamples {
+    pub energies: Vec<f64>,
+    pub observables: Vec<f64>,
+    pub ln_weights: Vec<f64>,
+}
+
+impl Multicanonical {
+    /// Flat initial weights (the T = ∞ ensemble).
+    pub fn new(grid: EnergyGrid) -> Self {
+        let num_bins = grid.num_bins;
+        Self {
+            grid,
+            ln_w: vec![0.0; num_bins],
+            histogram: vec![0; num_bins],
+            iteration: 0,
+            statistics: vec![0.0; num_bins],
+        }
+    }
+
+    /// Initial weights of the canonical ensemble at `temperature`, W(E) = exp(-E/T).
+    pub fn from_temperature(grid: EnergyGrid, temperature: f64) -> Self {
+        let mut muca = Self::new(grid);
+        for k in 0..muca.grid.num_bins {
+            muca.ln_w[k] = -muca.grid.energy(k) / temperature;
+        }
+        muca
+    }
+
+    fn current_bin<S: EnergyMoves>(&self, system: &S) -> usize {
+        self.grid
+            .bin(system.energy())
+            .expect("System energy outside the multicanonical window")
+    }
+
+    /// `moves` single moves with fixed weights, accumulating the histogram.
+    pub fn walk<S: EnergyMoves>(&mut self, system: &mut S, moves: usize) {
+        let mut rng = rand::rng();
+        let mut energy = system.energy();
+        let mut bin = self.current_bin(system);
+        for _ in 0..moves {
+            let (mv, delta_e) = system.propose(&mut rng);
+            if let Some(new_bin) = self.grid.bin(energy + delta_e) {
+                let ln_ratio = self.ln_w[new_bin] - self.ln_w[bin];
+                if ln_ratio >= 0.0 || rng.random::<f64>() < ln_ratio.exp() {
+                    system.apply(mv);
+                    energy += delta_e;
+                    bin = new_bin;
+                }
+            }
+            self.histogram[bin] += 1;
+        }
+    }
+
+    /// One refinement iteration: a fresh histogram over `moves` moves, then the
+    /// recursive weight update, shifted so that max ln W = 0.
+    pub fn iterate<S: EnergyMoves>(&mut self, system: &mut S, moves: usize) {
+        self.histogram.iter_mut().for_each(|h| *h = 0);
+        self.walk(system, moves);
+        let mut steps: Vec<f64> = self.ln_w.windows(2).map(|w| w[1] - w[0]).collect();
+        let visited: Vec<usize> = (0..self.grid.num_bins)
+            .filter(|&k| self.histogram[k] > 0)
+            .collect();
+        for pair in visited.windows(2) {
+            let (h, h_next) = (
+                self.histogram[pair[0]] as f64,
+                self.histogram[pair[1]] as f64,
+            );
+            let weight = h * h_next / (h + h_next);
+            self.statistics[pair[0]] += weight;
+            let kappa = weight / self.statistics[pair[0]];
+            // Bins strictly between the pair are unreachable; adjust the last step.
+            steps[pair[1] - 1] += kappa * (h / h_next).ln();
+        }
+        for (k, step) in steps.iter().enumerate() {
+            self.ln_w[k + 1] = self.ln_w[k] + step;
+        }
+        let max = self.ln_w.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        self.ln_w.iter_mut().for_each(|w| *w -= max);
+        self.iteration += 1;
+    }
+
+    /// Runs `iterations` refinement steps; with `output` set, the weights and
+    /// histogram of iteration n are written to `{output}_{n}.dat`.
+    pub fn refine<S: EnergyMoves>(
+        &mut self,
+        system: &mut S,
+        iterations: usize,
+        moves: usize,
+        output: Option<&str>,
+    ) -> std::io::Result<()> {
+        for _ in 0..iterations {
+            // The table shows the histogram that produced the new weights.
+            let histogram_weights = self.ln_w.clone();
+            self.iterate(system, moves);
+            if let Some(prefix) = output {
+                let path = format!("{}_{}.dat", prefix, self.iteration);
+                std::fs::write(
+                    path,
+                    Self::format_table(&self.grid, &histogram_weights, &self.histogram),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// min H / mean H over the visited bins; 1 for a perfectly flat histogram.
+    pub fn flatness(&self) -> f64 {
+        let visited: Vec<usize> = self.histogram.iter().cloned().filter(|&h| h > 0).collect();
+        if visited.is_empty() {
+            return 0.0;
+        }
+        let mean = visited.iter().sum::<usize>() as f64 / visited.len() as f64;
+        *visited.iter().min().unwrap() as f64 / mean
+    }
+
+    /// `E ln_W H` per bin.
+    pub fn table(&self) -> String {
+        Self::format_table(&self.grid, &self.ln_w, &self.histogram)
+    }
+
+    fn format_table(grid: &EnergyGrid, ln_w: &[f64], histogram: &[usize]) -> String {
+        let mut out = String::from("# E ln_W H\n");
+        for (k, (w, h)) in ln_w.iter().zip(histogram).enumerate() {
+            writeln!(out, "{:.6} {:.10} {}", grid.energy(k), w, h).unwrap();
+        }
+        out
+    }
+
+    pub fn write_table(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.table())
+    }
+
+    /// Restores the weights from a table written by `write_table` or `refine`;
+    /// the accumulated recursion statistics start afresh.
+    pub fn read_weights(&mut self, path: &str) -> std::io::Result<()> {
+        self.statistics.iter_mut().for_each(|p| *p = 0.0);
+        let text = std::fs::read_to_string(path)?;
+        for line in text
+            .lines()
+            .filter(|l| !l.starts_with('#') && !l.trim().is_empty())
+        {
+            let mut columns = line.split_whitespace();
+            let parse = |c: Option<&str>| -> std::io::Result<f64> {
+                c.and_then(|s| s.parse().ok()).ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, "Malformed weight table")
+                })
+            };
+            let energy = parse(columns.next())?;
+            let ln_w = parse(columns.next())?;
+            if let Some(k) = self.grid.bin(energy) {
+                self.ln_w[k] = ln_w;
+            }
+        }
+        Ok(())
+    }
+
+    /// Estimate of ln g(E) = ln H(E) - ln W(E) on visited bins (up to a constant);
+    /// the histogram must come from the current weights, e.g. after `production`.
+    pub fn ln_density_of_states(&self) -> Vec<Option<f64>> {
+        self.histogram
+            .iter()
+            .zip(&self.ln_w)
+            .map(|(&h, w)| (h > 0).then(|| (h as f64).ln() - w))
+            .collect()
+    }
+
+    /// Production run with fixed weights: records E, `observable` and ln W(E)
+    /// every `interval` moves.
+    pub fn production<S, F>(
+        &mut self,
+        system: &mut S,
+        samples: usize,
+        interval: usize,
+        mut observable: F,
+    ) -> MulticanonicalSamples
+    where
+        S: EnergyMoves,
+        F: FnMut(&S) -> f64,
+    {
+        self.histogram.iter_mut().for_each(|h| *h = 0);
+        let mut result = MulticanonicalSamples {
+            energies: Vec::with_capacity(samples),
+            observables: Vec::with_capacity(samples),
+            ln_weights: Vec::with_capacity(samples),
+        };
+        for _ in 0..samples {
+            self.walk(system, interval);
+            let energy = system.energy();
+            result.energies.push(energy);
+            result.observables.push(observable(system));
+            result.ln_weights.push(self.ln_w[self.current_bin(system)]);
+        }
+        result
+    }
+}
+
+impl MulticanonicalSamples {
+    /// Normalised canonical weight of every sample at `temperature`.
+    pub fn canonical_weights(&self, temperature: f64) -> Vec<f64> {
+        let exponents: Vec<f64> = self
+            .energies
+            .iter()
+            .zip(&self.ln_weights)
+            .map(|(e, w)| -e / temperature - w)
+            .collect();
+        let ln_norm = log_sum_exp(&exponents);
+        exponents.iter().map(|x| (x - ln_norm).exp()).collect()
+    }
+
+    pub fn energy(&self, temperature: f64) -> f64 {
+        let p = self.canonical_weights(temperature);
+        p.iter().zip(&self.energies).map(|(p, e)| p * e).sum()
+    }
+
+    /// Total heat capacity Var E / T².
+    pub fn specific_heat(&self, temperature: f64) -> f64 {
+        let p = self.canonical_weights(temperature);
+        let mean: f64 = p.iter().zip(&self.energies).map(|(p, e)| p * e).sum();
+        let second: f64 = p.iter().zip(&self.energies).map(|(p, e)| p * e * e).sum();
+        (second - mean * mean) / (temperature * temperature)
+    }
+
+    pub fn observable(&self, temperature: f64) -> f64 {
+        let p = self.canonical_weights(temperature);
+        p.iter().zip(&self.observables).map(|(p, o)| p * o).sum()
+    }
+
+    /// Canonical energy distribution at `temperature` on the bins of `grid`.
+    pub fn energy_distribution(&self, grid: &EnergyGrid, temperature: f64) -> Vec<f64> {
+        let mut distribution = vec![0.0; grid.num_bins];
+        for (p, &e) in self
+            .canonical_weights(temperature)
+            .iter()
+            .zip(&self.energies)
+        {
+            if let Some(k) = grid.bin(e) {
+                distribution[k] += p;
+            }
+        }
+        distribution
+    }
+}
+
+pub fn multicanonical_example() {
+    // q = 10 Potts at its first-order transition: the canonical energy
+    // distribution has two peaks separated by a strongly suppressed valley.
+    let tc = square_lattice_critical_temperature(10, 1.0);
+    let mut model = PottsModel::new(8, 10, 1.0, tc);
+    let n = model.num_spins() as f64;
+    // Window around the ordered (e ≈ -1.66) and disordered (e ≈ -0.97) peaks.
+    let grid = EnergyGrid::new(-1.9 * n, -0.7 * n, 1.0);
+    while grid.bin(model.energy()).is_none() {
+        model.metropolis_sweep();
+    }
+    let mut muca = Multicanonical::from_temperature(grid, tc);
+    muca.refine(&mut model, 10, 100_000, None).unwrap();
+    println!(
+        "Multicanonical q = 10 Potts (8×8): flatness {:.2} after {} iterations",
+        muca.flatness(),
+        muca.iteration
+    );
+    let samples = muca.production(&mut model, 10_000, 64, |m| m.order_parameter());
+    let p = samples.energy_distribution(&muca.grid, tc);
+    let peak =
+        |range: std::ops::Range<usize>| range.max_by(|&a, &b| p[a].total_cmp(&p[b])).unwrap();
+    let (ordered, disordered) = (peak(0..p.len() / 2), peak(p.len() / 2..p.len()));
+    let valley = p[ordered..disordered]
+        .iter()
+        .cloned()
+        .fold(f64::INFINITY, f64::min);
+    println!(
+        "At T_c: e = {:.4}, m = {:.4}, P_min / P_max = {:.2e}",
+        samples.energy(tc) / n,
+        samples.observable(tc),
+        valley / p[ordered].max(p[disordered])
+    );
+}
+
+#[test]
+fn test_multicanonical_ising() {
+    use crate::physics::ising_model::IsingModel;
+    let mut model = IsingModel::new(4, 1.0);
+    let grid = EnergyGrid::ising(&model);
+    let mut muca = Multicanonical::new(grid);
+    muca.refine(&mut model, 15, 100_000, None).unwrap();
+    let samples = muca.production(&mut model, 50_000, 16, |m| m.magnetization().abs());
+
+    // g(-24) / g(-32) = 32 / 2 on the 4×4 periodic lattice.
+    let ln_g = muca.ln_density_of_states();
+    let k0 = muca.grid.bin(-32.0).unwrap();
+    let k1 = muca.grid.bin(-24.0).unwrap();
+    let ratio = ln_g[k1].unwrap() - ln_g[k0].unwrap();
+    assert!((ratio - 16f64.ln()).abs() < 0.2, "ln ratio {}", ratio);
+
+    // Reweighting reproduces a direct canonical simulation.
+    let direct = IsingModel::new(4, 2.5).measure(1000, 20_000);
+    let e = samples.energy(2.5) / 16.0;
+    assert!((e - direct.energy.mean).abs() < 5.0 * direct.energy.error + 0.02);
+    let m = samples.observable(2.5);
+    assert!(
+        (m - direct.abs_magnetization.mean).abs() < 5.0 * direct.abs_magnetization.error + 0.02
+    );
+}
+
+#[test]
+fn test_multicanonical_weight_files() {
+    let mut model = PottsModel::new(4, 3, 1.0, 1.0);
+    let mut muca = Multicanonical::new(EnergyGrid::potts(&model));
+    let prefix = std::env::temp_dir().join(format!("muca_{}", std::process::id()));
+    let prefix = prefix.to_str().unwrap();
+    muca.refine(&mut model, 2, 10_000, Some(prefix)).unwrap();
+    let final_table = format!("{}_final.dat", prefix);
+    muca.write_table(&final_table).unwrap();
+    let mut restored = Multicanonical::new(EnergyGrid::potts(&model));
+    restored.read_weights(&final_table).unwrap();
+    for (a, b) in muca.ln_w.iter().zip(&restored.ln_w) {
+        assert!((a - b).abs() < 1e-8);
+    }
+    for suffix in ["1", "2", "final"] {
+        std::fs::remove_file(format!("{}_{}.dat", prefix, suffix)).unwrap();
+    }
+}
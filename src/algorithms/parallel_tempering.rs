@@ -0,0 +1,345 @@
+use crate::physics::hilbert::Hamiltonian;
+use rand::Rng;
+
+/// A system that can take part in replica exchange
+/// - Exposes its energy and accepts a new temperature after a swap
+/// - `sweep` performs one unit of equilibrium dynamics at the current temperature
+pub trait Replica {
+    fn energy(&self) -> f64;
+    fn temperature(&self) -> f64;
+    fn set_temperature(&mut self, temperature: f64);
+    fn sweep(&mut self);
+}
+
+/// Adapter that turns any `Hamiltonian<S>` plus a proposal move into a `Replica`
+/// - One sweep is `moves_per_sweep` Metropolis steps with the symmetric `propose` move
+pub struct HamiltonianReplica<H, S, P> {
+    pub hamiltonian: H,
+    pub state: S,
+    pub temperature: f64,
+    pub moves_per_sweep: usize,
+    pub propose: P,
+}
+
+impl<H, S, P> HamiltonianReplica<H, S, P>
+where
+    H: Hamiltonian<S>,
+    P: Fn(&S) -> S,
+{
+    pub fn new(
+        hamiltonian: H,
+        state: S,
+        temperature: f64,
+        moves_per_sweep: usize,
+        propose: P,
+    ) -> Self {
+        Self {
+            hamiltonian,
+            state,
+            temperature,
+            moves_per_sweep,
+            propose,
+        }
+    }
+}
+
+impl<H, S, P> Replica for HamiltonianReplica<H, S, P>
+where
+    H: Hamiltonian<S>,
+    P: Fn(&S) -> S,
+{
+    fn energy(&self) -> f64 {
+        self.hamiltonian.energy(&self.state)
+    }
+    fn temperature(&self) -> f64 {
+        self.temperature
+    }
+    fn set_temperature(&mut self, temperature: f64) {
+        self.temperature = temperature;
+    }
+    fn sweep(&mut self) {
+        let mut rng = rand::rng();
+        let mut current = self.hamiltonian.energy(&self.state);
+        for _ in 0..self.moves_per_sweep {
+            let proposal = (self.propose)(&self.state);
+            let energy = self.hamiltonian.energy(&proposal);
+            let delta_e = energy - current;
+            if delta_e <= 0.0 || rng.random::<f64>() < (-delta_e / self.temperature).exp() {
+                self.state = proposal;
+                current = energy;
+            }
+        }
+    }
+}
+
+/// Direction label of a walker for round-trip bookkeeping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    Unlabelled,
+    /// Last visited the lowest temperature.
+    Up,
+    /// Last visited the highest temperature.
+    Down,
+}
+
+/// Parallel Tempering (replica exchange Monte Carlo)
+/// - `replicas[k]` always runs at `temperatures[k]`; accepted swaps exchange configurations
+/// - Neighbour swaps (k, k + 1) are accepted with min(1, exp((β_k - β_k+1)(E_k - E_k+1)))
+/// - Walkers are tracked through the ladder to count round trips lowest → highest → lowest
+/// - `tune_temperatures` applies the feedback-optimised update that equalises round-trip times
+pub struct ParallelTempering<R: Replica> {
+    pub replicas: Vec<R>,
+    pub temperatures: Vec<f64>,
+    pub swap_attempts: Vec<usize>,
+    pub swap_accepts: Vec<usize>,
+    /// Completed round trips per walker.
+    pub round_trips: Vec<usize>,
+    pub sweeps: usize,
+    walker_at: Vec<usize>,
+    directions: Vec<Direction>,
+    up_counts: Vec<usize>,
+    down_counts: Vec<usize>,
+}
+
+impl<R: Replica> ParallelTempering<R> {
+    /// `temperatures` must be strictly increasing and match the number of replicas.
+    pub fn new(mut replicas: Vec<R>, temperatures: Vec<f64>) -> Self {
+        assert_eq!(
+            replicas.len(),
+            temperatures.len(),
+            "One temperature per replica"
+        );
+        assert!(replicas.len() >= 2, "Need at least two replicas");
+        assert!(
+            temperatures.windows(2).all(|w| w[0] < w[1]),
+            "Temperatures must be strictly increasing"
+        );
+        for (replica, &t) in replicas.iter_mut().zip(&temperatures) {
+            replica.set_temperature(t);
+        }
+        let m = replicas.len();
+        let mut directions = vec![Direction::Unlabelled; m];
+        directions[0] = Direction::Up;
+        directions[m - 1] = Direction::Down;
+        Self {
+            replicas,
+            temperatures,
+            swap_attempts: vec![0; m - 1],
+            swap_accepts: vec![0; m - 1],
+            round_trips: vec![0; m],
+            sweeps: 0,
+            walker_at: (0..m).collect(),
+            directions,
+            up_counts: vec![0; m],
+            down_counts: vec![0; m],
+        }
+    }
+
+    /// One sweep of every replica followed by swap attempts on the even or odd
+    /// neighbour pairs (alternating between calls).
+    pub fn step(&mut self) {
+        for replica in self.replicas.iter_mut() {
+            replica.sweep();
+        }
+        let mut rng = rand::rng();
+        let m = self.replicas.len();
+        for k in (self.sweeps % 2..m - 1).step_by(2) {
+            self.swap_attempts[k] += 1;
+            let beta_lo = 1.0 / self.temperatures[k];
+            let beta_hi = 1.0 / self.temperatures[k + 1];
+            let delta =
+                (beta_lo - beta_hi) * (self.replicas[k].energy() - self.replicas[k + 1].energy());
+            if delta >= 0.0 || rng.random::<f64>() < delta.exp() {
+                self.replicas.swap(k, k + 1);
+                self.replicas[k].set_temperature(self.temperatures[k]);
+                self.replicas[k + 1].set_temperature(self.temperatures[k + 1]);
+                self.walker_at.swap(k, k + 1);
+                self.swap_accepts[k] += 1;
+            }
+        }
+        self.sweeps += 1;
+        self.update_labels();
+    }
+
+    pub fn run(&mut self, sweeps: usize) {
+        for _ in 0..sweeps {
+            self.step();
+        }
+    }
+
+    fn update_labels(&mut self) {
+        let m = self.replicas.len();
+        let bottom = self.walker_at[0];
+        if self.directions[bottom] == Direction::Down {
+            self.round_trips[bottom] += 1;
+        }
+        self.directions[bottom] = Direction::Up;
+        self.directions[self.walker_at[m - 1]] = Direction::Down;
+        for k in 0..m {
+            match self.directions[self.walker_at[k]] {
+                Direction::Up => self.up_counts[k] += 1,
+                Direction::Down => self.down_counts[k] += 1,
+                Direction::Unlabelled => {}
+            }
+        }
+    }
+
+    /// Fraction of accepted swaps for each neighbour pair (k, k + 1).
+    pub fn swap_acceptance(&self) -> Vec<f64> {
+        self.swap_accepts
+            .iter()
+            .zip(&self.swap_attempts)
+            .map(|(&a, &n)| if n == 0 { 0.0 } else { a as f64 / n as f64 })
+            .collect()
+    }
+
+    /// Fraction f(T_k) of labelled walkers at each temperature that last visited the
+    /// lowest temperature; optimal ladders make f fall linearly from 1 to 0.
+    pub fn up_fraction(&self) -> Vec<f64> {
+        self.up_counts
+            .iter()
+            .zip(&self.down_counts)
+            .map(|(&u, &d)| {
+                if u + d == 0 {
+                    0.0
+                } else {
+                    u as f64 / (u + d) as f64
+                }
+            })
+            .collect()
+    }
+
+    /// Mean number of sweeps per completed round trip, if any walker finished one.
+    pub fn mean_round_trip_time(&self) -> Option<f64> {
+        let trips: usize = self.round_trips.iter().sum();
+        (trips > 0).then(|| (self.sweeps * self.replicas.len()) as f64 / trips as f64)
+    }
+
+    /// Feedback-optimised temperature update (Katzgraber, Trebst, Huse, Troyer)
+    /// - Redistributes the interior temperatures with density η(T) ∝ sqrt(|df/dT| / ΔT)
+    /// - Keeps the end points fixed and resets all statistics
+    pub fn tune_temperatures(&mut self) {
+        let m = self.temperatures.len();
+        let f = self.up_fraction();
+        let widths: Vec<f64> = self.temperatures.windows(2).map(|w| w[1] - w[0]).collect();
+        // Weight of each interval: ∫ η dT = sqrt(|Δf|) up to normalisation; a small
+        // floor keeps intervals without statistics from collapsing.
+        let weights: Vec<f64> = (0..m - 1)
+            .map(|k| (f[k] - f[k + 1]).abs().max(1e-3).sqrt())
+            .collect();
+        let total: f64 = weights.iter().sum();
+
+        let mut new_temperatures = vec![self.temperatures[0]];
+        let mut k = 0;
+        let mut accumulated = 0.0;
+        for j in 1..m - 1 {
+            let target = total * j as f64 / (m - 1) as f64;
+            while k < m - 2 && accumulated + weights[k] < target {
+                accumulated += weights[k];
+                k += 1;
+            }
+            let fraction = (target - accumulated) / weights[k];
+            new_temperatures.push(self.temperatures[k] + fraction * widths[k]);
+        }
+        new_temperatures.push(self.temperatures[m - 1]);
+
+        self.temperatures = new_temperatures;
+        for (replica, &t) in self.replicas.iter_mut().zip(&self.temperatures) {
+            replica.set_temperature(t);
+        }
+        self.reset_statistics();
+    }
+
+    pub fn reset_statistics(&mut self) {
+        let m = self.replicas.len();
+        self.swap_attempts = vec![0; m - 1];
+        self.swap_accepts = vec![0; m - 1];
+        self.round_trips = vec![0; m];
+        self.up_counts = vec![0; m];
+        self.down_counts = vec![0; m];
+        self.sweeps = 0;
+    }
+}
+
+/// m temperatures spaced geometrically between `t_min` and `t_max`.
+pub fn geometric_temperatures(t_min: f64, t_max: f64, m: usize) -> Vec<f64> {
+    assert!(m >= 2, "Need at least two temperatures");
+    let ratio = (t_max / t_min).powf(1.0 / (m - 1) as f64);
+    (0..m).map(|k| t_min * ratio.powi(k as i32)).collect()
+}
+
+pub fn parallel_tempering_example() {
+    use crate::physics::ising_model::IsingModel;
+    let temperatures = geometric_temperatures(1.5, 3.5, 8);
+    let replicas = temperatures
+        .iter()
+        .map(|&t| IsingModel::new(16, t))
+        .collect();
+    let mut pt = ParallelTempering::new(replicas, temperatures);
+    for _ in 0..3 {
+        pt.run(500);
+        println!("Swap acceptance: {:?}", pt.swap_acceptance());
+        pt.tune_temperatures();
+    }
+    println!("Tuned temperatures: {:?}", pt.temperatures);
+}
+
+#[test]
+fn test_parallel_tempering_ising() {
+    use crate::physics::ising_model::IsingModel;
+    let temperatures = geometric_temperatures(1.5, 4.0, 6);
+    let replicas = temperatures
+        .iter()
+        .map(|&t| IsingModel::new(8, t))
+        .collect();
+    let mut pt = ParallelTempering::new(replicas, temperatures.clone());
+    pt.run(400);
+    let acceptance = pt.swap_acceptance();
+    assert!(acceptance.iter().all(|&a| a > 0.0 && a <= 1.0));
+    // Replicas stay attached to their ladder slot after swaps.
+    for (replica, &t) in pt.replicas.iter().zip(&pt.temperatures) {
+        assert_eq!(replica.temperature, t);
+    }
+    assert!(pt.replicas[0].energy() < pt.replicas[5].energy());
+
+    pt.tune_temperatures();
+    assert_eq!(pt.temperatures[0], temperatures[0]);
+    assert_eq!(pt.temperatures[5], temperatures[5]);
+    assert!(pt.temperatures.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn test_parallel_tempering_double_well() {
+    // E(x) = 10 (x² - 1)²: at T = 0.1 plain Metropolis stays in one well.
+    struct DoubleWell;
+    impl Hamiltonian<f64> for DoubleWell {
+        fn energy(&self, x: &f64) -> f64 {
+            10.0 * (x * x - 1.0).powi(2)
+        }
+    }
+    let temperatures = geometric_temperatures(0.1, 10.0, 8);
+    let replicas = temperatures
+        .iter()
+        .map(|&t| {
+            HamiltonianReplica::new(DoubleWell, 1.0, t, 10, |x: &f64| {
+                x + rand::rng().random_range(-0.3..0.3)
+            })
+        })
+        .collect();
+    let mut pt = ParallelTempering::new(replicas, temperatures);
+    let mut right = 0;
+    let samples = 4000;
+    for _ in 0..samples {
+        pt.step();
+        if pt.replicas[0].state > 0.0 {
+            right += 1;
+        }
+    }
+    let fraction = right as f64 / samples as f64;
+    assert!(
+        (fraction - 0.5).abs() < 0.25,
+        "fraction in right well {}",
+        fraction
+    );
+    assert!(pt.round_trips.iter().sum::<usize>() > 0);
+}
@@ -0,0 +1,352 @@
+use crate::algorithms::wang_landau::log_sum_exp;
+use crate::diagnostics::{integrated_autocorrelation_time, Estimate};
+use crate::physics::blume_capel::BlumeCapelObservables;
+use crate::physics::ising_model::{IsingModel, IsingObservables};
+
+/// Time series of one canonical simulation
+/// - `energies[i]` is the total energy of measurement i
+/// - `observables[j][i]` is observable j at measurement i
+pub struct HistogramRun {
+    pub temperature: f64,
+    pub energies: Vec<f64>,
+    pub observables: Vec<Vec<f64>>,
+}
+
+impl HistogramRun {
+    pub fn new(temperature: f64, energies: Vec<f64>, observables: Vec<Vec<f64>>) -> Self {
+        for series in &observables {
+            assert_eq!(series.len(), energies.len(), "Series must be equally long");
+        }
+        Self {
+            temperature,
+            energies,
+            observables,
+        }
+    }
+
+    /// Total energies with observables [|m|, m²] from an Ising measurement.
+    pub fn from_ising(obs: &IsingObservables) -> Self {
+        let n = obs.num_spins as f64;
+        Self::new(
+            obs.temperature,
+            obs.energy_series.iter().map(|e| e * n).collect(),
+            vec![
+                obs.magnetization_series.iter().map(|m| m.abs()).collect(),
+                obs.magnetization_series.iter().map(|m| m * m).collect(),
+            ],
+        )
+    }
+
+    /// Total energies with observables [|m|, m², q] from a Blume–Capel measurement;
+    /// D and J stay fixed, so only the temperature is reweighted.
+    pub fn from_blume_capel(obs: &BlumeCapelObservables) -> Self {
+        let mut run = Self::from_ising(&obs.thermo);
+        run.observables.push(obs.quadrupole_series.clone());
+        run
+    }
+}
+
+/// Canonical averages reweighted to `temperature`
+/// - `energy` is the mean total energy, `specific_heat` the total Var E / T²
+/// - `observables[j]` is the reweighted mean of observable j
+pub struct ReweightedObservables {
+    pub temperature: f64,
+    pub energy: Estimate,
+    pub specific_heat: Estimate,
+    pub observables: Vec<Estimate>,
+}
+
+/// Pooled samples of all runs, each carrying ln Σ_m n_m exp(-β_m E_i - ln Z_m) - ln w_i
+/// with n_m the effective count of run m and w_i = 1 / g the weight of a sample
+/// from a run with statistical inefficiency g.
+struct Pool {
+    energies: Vec<f64>,
+    observables: Vec<Vec<f64>>,
+    counts: Vec<f64>,
+    ln_weights: Vec<f64>,
+    ln_denominators: Vec<f64>,
+}
+
+impl Pool {
+    fn set_ln_z(&mut self, temperatures: &[f64], ln_z: &[f64]) {
+        let mut terms = vec![0.0; temperatures.len()];
+        for ((d, e), w) in self
+            .ln_denominators
+            .iter_mut()
+            .zip(&self.energies)
+            .zip(&self.ln_weights)
+        {
+            for (m, t) in temperatures.iter().enumerate() {
+                terms[m] = self.counts[m].ln() - e / t - ln_z[m];
+            }
+            *d = log_sum_exp(&terms) - w;
+        }
+    }
+}
+
+/// Ferrenberg–Swendsen histogram reweighting
+/// - With one run this is single-histogram reweighting; with several, the relative
+///   partition functions Z_m are solved self-consistently (WHAM) from
+///   Z_k = Σ_i exp(-β_k E_i) / Σ_m n_m exp(-β_m E_i) / Z_m over all pooled samples
+/// - Works on the raw time series, so no energy binning is needed
+/// - Run m enters with its effective count n_m / g_m, g_m = 2 τ_int of its energy
+///   series, and each of its samples with weight 1 / g_m, so strongly correlated runs
+///   near T_c are not over-weighted
+/// - Errors: blocked jackknife, dropping the same block of every run and re-solving
+pub struct MultiHistogram {
+    pub runs: Vec<HistogramRun>,
+    /// Statistical inefficiency g = 2 τ_int of every run's energy series.
+    pub inefficiencies: Vec<f64>,
+    /// ln Z of every run relative to the first.
+    pub ln_z: Vec<f64>,
+    pub num_blocks: usize,
+    pub tolerance: f64,
+}
+
+impl MultiHistogram {
+    pub fn new(runs: Vec<HistogramRun>) -> Self {
+        assert!(!runs.is_empty(), "Need at least one run");
+        let num_observables = runs[0].observables.len();
+        assert!(
+            runs.iter().all(|r| r.observables.len() == num_observables),
+            "All runs must record the same observables"
+        );
+        let min_len = runs.iter().map(|r| r.energies.len()).min().unwrap();
+        let mut histogram = Self {
+            ln_z: vec![0.0; runs.len()],
+            inefficiencies: runs
+                .iter()
+                .map(|r| 2.0 * integrated_autocorrelation_time(&r.energies))
+                .collect(),
+            runs,
+            num_blocks: min_len.clamp(2, 20),
+            tolerance: 1e-10,
+        };
+        histogram.ln_z = histogram.solve(None, histogram.ln_z.clone());
+        histogram
+    }
+
+    /// Index range of jackknife block `b` in a series of length `n`; the last
+    /// block absorbs the remainder.
+    fn block(&self, n: usize, b: usize) -> std::ops::Range<usize> {
+        let len = n / self.num_blocks;
+        let end = if b + 1 == self.num_blocks {
+            n
+        } else {
+            (b + 1) * len
+        };
+        b * len..end
+    }
+
+    /// Pools the samples of all runs, leaving out jackknife block `skip` of each.
+    fn pool(&self, skip: Option<usize>, ln_z: &[f64]) -> Pool {
+        let mut energies = Vec::new();
+        let mut observables = vec![Vec::new(); self.runs[0].observables.len()];
+        let mut counts = Vec::with_capacity(self.runs.len());
+        let mut ln_weights = Vec::new();
+        for (run, &g) in self.runs.iter().zip(&self.inefficiencies) {
+            let n = run.energies.len();
+            let dropped = skip.map_or(0..0, |b| self.block(n, b));
+            let keep = |i: &usize| !dropped.contains(i);
+            energies.extend((0..n).filter(keep).map(|i| run.energies[i]));
+            for (pooled, series) in observables.iter_mut().zip(&run.observables) {
+                pooled.extend((0..n).filter(keep).map(|i| series[i]));
+            }
+            let kept = n - dropped.len();
+            counts.push(kept as f64 / g);
+            ln_weights.extend(std::iter::repeat_n(-g.ln(), kept));
+        }
+        let mut pool = Pool {
+            ln_denominators: vec![0.0; energies.len()],
+            energies,
+            observables,
+            counts,
+            ln_weights,
+        };
+        pool.set_ln_z(&self.temperatures(), ln_z);
+        pool
+    }
+
+    fn temperatures(&self) -> Vec<f64> {
+        self.runs.iter().map(|r| r.temperature).collect()
+    }
+
+    /// WHAM fixed-point iteration for ln Z, normalised to ln Z_0 = 0.
+    fn solve(&self, skip: Option<usize>, mut ln_z: Vec<f64>) -> Vec<f64> {
+        let temperatures = self.temperatures();
+        let mut pool = self.pool(skip, &ln_z);
+        for _ in 0..10_000 {
+            let mut new_ln_z: Vec<f64> = temperatures.iter().map(|&t| ln_z_at(&pool, t)).collect();
+            let offset = new_ln_z[0];
+            new_ln_z.iter_mut().for_each(|z| *z -= offset);
+            let change = new_ln_z
+                .iter()
+                .zip(&ln_z)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f64::max);
+            ln_z = new_ln_z;
+            if change < self.tolerance {
+                break;
+            }
+            pool.set_ln_z(&temperatures, &ln_z);
+        }
+        ln_z
+    }
+
+    /// Reweighted averages at `temperature`, which should lie within the energy
+    /// range sampled by the runs.
+    pub fn at(&self, temperature: f64) -> ReweightedObservables {
+        let evaluate = |pool: &Pool| -> Vec<f64> {
+            let p = probabilities(pool, temperature);
+            let mean = dot(&p, &pool.energies);
+            let second: f64 = p.iter().zip(&pool.energies).map(|(p, e)| p * e * e).sum();
+            let mut values = vec![mean, (second - mean * mean) / (temperature * temperature)];
+            values.extend(pool.observables.iter().map(|o| dot(&p, o)));
+            values
+        };
+        let full = evaluate(&self.pool(None, &self.ln_z));
+        let leave_one_out: Vec<Vec<f64>> = (0..self.num_blocks)
+            .map(|b| {
+                let ln_z = if self.runs.len() == 1 {
+                    self.ln_z.clone()
+                } else {
+                    self.solve(Some(b), self.ln_z.clone())
+                };
+                evaluate(&self.pool(Some(b), &ln_z))
+            })
+            .collect();
+
+        let k = self.num_blocks as f64;
+        let estimates: Vec<Estimate> = (0..full.len())
+            .map(|j| {
+                let mean = leave_one_out.iter().map(|v| v[j]).sum::<f64>() / k;
+                let var = leave_one_out
+                    .iter()
+                    .map(|v| (v[j] - mean).powi(2))
+                    .sum::<f64>()
+                    * (k - 1.0)
+                    / k;
+                Estimate {
+                    mean: full[j],
+                    error: var.sqrt(),
+                }
+            })
+            .collect();
+        ReweightedObservables {
+            temperature,
+            energy: estimates[0],
+            specific_heat: estimates[1],
+            observables: estimates[2..].to_vec(),
+        }
+    }
+
+    /// ln Z(T) relative to the first run.
+    pub fn ln_partition_function(&self, temperature: f64) -> f64 {
+        ln_z_at(&self.pool(None, &self.ln_z), temperature)
+    }
+
+    /// Reweighted curves on a set of temperatures.
+    pub fn curve(&self, temperatures: &[f64]) -> Vec<ReweightedObservables> {
+        temperatures.iter().map(|&t| self.at(t)).collect()
+    }
+}
+
+fn ln_z_at(pool: &Pool, temperature: f64) -> f64 {
+    let exponents: Vec<f64> = pool
+        .energies
+        .iter()
+        .zip(&pool.ln_denominators)
+        .map(|(e, d)| -e / temperature - d)
+        .collect();
+    log_sum_exp(&exponents)
+}
+
+fn probabilities(pool: &Pool, temperature: f64) -> Vec<f64> {
+    let ln_z = ln_z_at(pool, temperature);
+    pool.energies
+        .iter()
+        .zip(&pool.ln_denominators)
+        .map(|(e, d)| (-e / temperature - d - ln_z).exp())
+        .collect()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+pub fn reweighting_example() {
+    let runs = [2.2, 2.3, 2.4]
+        .iter()
+        .map(|&t| {
+            let obs = IsingModel::new(16, t).measure_with(200, 2000, |m| {
+                m.wolff_sweep();
+            });
+            HistogramRun::from_ising(&obs)
+        })
+        .collect();
+    let histogram = MultiHistogram::new(runs);
+    for r in histogram.curve(&[2.2, 2.25, 2.3, 2.35, 2.4]) {
+        println!(
+            "T = {:.3}: c = {:.4} ± {:.4}, |m| = {:.4} ± {:.4}",
+            r.temperature,
+            r.specific_heat.mean / 256.0,
+            r.specific_heat.error / 256.0,
+            r.observables[0].mean,
+            r.observables[0].error
+        );
+    }
+}
+
+#[test]
+fn test_single_histogram_identity() {
+    // Reweighting a run to its own temperature gives the plain averages.
+    let obs = IsingModel::new(8, 2.5).measure(200, 1000);
+    let histogram = MultiHistogram::new(vec![HistogramRun::from_ising(&obs)]);
+    let r = histogram.at(2.5);
+    assert!((r.energy.mean / 64.0 - obs.energy.mean).abs() < 1e-9);
+    assert!((r.observables[0].mean - obs.abs_magnetization.mean).abs() < 1e-9);
+    assert!(r.energy.error > 0.0);
+}
+
+#[test]
+fn test_multi_histogram_matches_exact_enumeration() {
+    use crate::models::lattice::{Boundary, Lattice};
+    use crate::physics::exact::ExactEnumeration;
+    // Metropolis runs on either side of the 4×4 crossover, reweighted to an
+    // intermediate temperature and checked against the exact averages.
+    let lattice = Lattice::square(4, Boundary::Periodic);
+    let runs: Vec<HistogramRun> = [2.0, 2.7]
+        .iter()
+        .map(|&t| {
+            let obs = IsingModel::with_lattice(lattice.clone(), t).measure(1000, 40_000);
+            HistogramRun::from_ising(&obs)
+        })
+        .collect();
+    let histogram = MultiHistogram::new(runs);
+    assert!(histogram.inefficiencies.iter().all(|&g| g >= 1.0));
+    let exact = ExactEnumeration::new(&lattice).at(2.35);
+    let r = histogram.at(2.35);
+    let e = r.energy.mean / 16.0;
+    assert!(
+        (e - exact.energy).abs() < 5.0 * r.energy.error / 16.0,
+        "reweighted e = {} ± {}, exact e = {}",
+        e,
+        r.energy.error / 16.0,
+        exact.energy
+    );
+    let m = r.observables[0];
+    assert!(
+        (m.mean - exact.abs_magnetization).abs() < 5.0 * m.error,
+        "reweighted |m| = {} ± {}, exact |m| = {}",
+        m.mean,
+        m.error,
+        exact.abs_magnetization
+    );
+    let c = r.specific_heat.mean / 16.0;
+    assert!(
+        (c - exact.specific_heat).abs() < 5.0 * r.specific_heat.error / 16.0,
+        "reweighted c = {}, exact c = {}",
+        c,
+        exact.specific_heat
+    );
+}
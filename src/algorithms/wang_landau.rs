@@ -0,0 +1,430 @@
+use crate::algorithms::parallel_tempering::HamiltonianReplica;
+use crate::physics::hilbert::Hamiltonian;
+use crate::physics::ising_model::IsingModel;
+use crate::physics::potts::PottsModel;
+use rand::Rng;
+
+/// A system with local moves whose energy change can be evaluated before applying them
+/// - Flat-histogram samplers (Wang–Landau, multicanonical) decide acceptance themselves
+///   from the proposed energy change
+pub trait EnergyMoves {
+    type Move;
+    fn energy(&self) -> f64;
+    /// Draws a random move and returns it with its energy change ΔE.
+    fn propose<R: Rng>(&self, rng: &mut R) -> (Self::Move, f64);
+    fn apply(&mut self, mv: Self::Move);
+}
+
+impl EnergyMoves for IsingModel {
+    type Move = usize;
+    fn energy(&self) -> f64 {
+        IsingModel::energy(self)
+    }
+    fn propose<R: Rng>(&self, rng: &mut R) -> (usize, f64) {
+        let site = rng.random_range(0..self.num_spins());
+        (site, self.delta_energy(site))
+    }
+    fn apply(&mut self, site: usize) {
+        self.spins[site] = -self.spins[site];
+    }
+}
+
+impl EnergyMoves for PottsModel {
+    type Move = (usize, usize);
+    fn energy(&self) -> f64 {
+        PottsModel::energy(self)
+    }
+    fn propose<R: Rng>(&self, rng: &mut R) -> ((usize, usize), f64) {
+        let site = rng.random_range(0..self.num_spins());
+        let old = self.spins[site];
+        let new = (old + rng.random_range(1..self.q)) % self.q;
+        let (mut n_old, mut n_new) = (0, 0);
+        for n in &self.lattice.neighbors[site] {
+            let s = self.spins[n.site];
+            n_old += (s == old) as i32;
+            n_new += (s == new) as i32;
+        }
+        ((site, new), -self.coupling * (n_new - n_old) as f64)
+    }
+    fn apply(&mut self, (site, state): (usize, usize)) {
+        self.spins[site] = state;
+    }
+}
+
+/// Any `Hamiltonian` with a proposal move; the temperature of the replica is ignored.
+impl<H, S, P> EnergyMoves for HamiltonianReplica<H, S, P>
+where
+    H: Hamiltonian<S>,
+    P: Fn(&S) -> S,
+{
+    type Move = S;
+    fn energy(&self) -> f64 {
+        self.hamiltonian.energy(&self.state)
+    }
+    fn propose<R: Rng>(&self, _rng: &mut R) -> (S, f64) {
+        let proposal = (self.propose)(&self.state);
+        let delta_e = self.hamiltonian.energy(&proposal) - self.hamiltonian.energy(&self.state);
+        (proposal, delta_e)
+    }
+    fn apply(&mut self, state: S) {
+        self.state = state;
+    }
+}
+
+/// Uniform energy bins [e_min, e_min + (num_bins - 1) * bin_width], centred on
+/// the allowed levels of a discrete-energy system.
+#[derive(Clone, Debug)]
+pub struct EnergyGrid {
+    pub e_min: f64,
+    pub bin_width: f64,
+    pub num_bins: usize,
+}
+
+impl EnergyGrid {
+    pub fn new(e_min: f64, e_max: f64, bin_width: f64) -> Self {
+        assert!(e_max >= e_min && bin_width > 0.0, "Invalid energy range");
+        Self {
+            e_min,
+            bin_width,
+            num_bins: ((e_max - e_min) / bin_width).round() as usize + 1,
+        }
+    }
+
+    /// Ising levels E = -B + 2k on a lattice with B bonds; needs |J| = 1 on every
+    /// bond and no field or second neighbours (use `new` otherwise).
+    pub fn ising(model: &IsingModel) -> Self {
+        let c = model.couplings();
+        assert!(
+            c.is_cluster_compatible() && c.j.iter().all(|j| j.abs() == 1.0),
+            "EnergyGrid::ising needs unit couplings without field"
+        );
+        let bonds = model.lattice.bonds.len() as f64;
+        Self::new(-bonds, bonds, 2.0)
+    }
+
+    /// Potts levels E = -J k, k = 0..=B satisfied bonds.
+    pub fn potts(model: &PottsModel) -> Self {
+        let extreme = -model.coupling * model.lattice.bonds.len() as f64;
+        Self::new(extreme.min(0.0), extreme.max(0.0), model.coupling.abs())
+    }
+
+    pub fn bin(&self, energy: f64) -> Option<usize> {
+        let k = ((energy - self.e_min) / self.bin_width).round();
+        (k >= 0.0 && (k as usize) < self.num_bins).then_some(k as usize)
+    }
+
+    pub fn energy(&self, bin: usize) -> f64 {
+        self.e_min + bin as f64 * self.bin_width
+    }
+}
+
+/// Modification-factor schedule of a Wang–Landau run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Schedule {
+    /// ln f → ln f / 2 whenever the histogram is flat.
+    Halving,
+    /// Halving until ln f < 1/t, then ln f = 1/t with t the number of moves per
+    /// visited bin (Belardinelli–Pereyra); avoids the saturation of plain halving.
+    InverseTime,
+}
+
+/// Wang–Landau estimate of the density of states g(E)
+/// - Random walk in energy accepted with min(1, g(E) / g(E')), adding ln f to ln g(E)
+///   and one count to the histogram at every visited level
+/// - Flatness: every visited bin has at least `flatness` × mean histogram count
+/// - Runs until ln f drops below `final_ln_f`; `density_of_states` gives the result
+pub struct WangLandau {
+    pub grid: EnergyGrid,
+    pub ln_g: Vec<f64>,
+    pub histogram: Vec<usize>,
+    pub visited: Vec<bool>,
+    pub ln_f: f64,
+    pub final_ln_f: f64,
+    pub flatness: f64,
+    pub schedule: Schedule,
+    /// Total number of proposed moves.
+    pub moves: usize,
+    /// Number of completed flat-histogram stages.
+    pub stages: usize,
+    inverse_time: bool,
+}
+
+impl WangLandau {
+    /// ln f = 1, flatness 0.8.
+    pub fn new(grid: EnergyGrid, schedule: Schedule, final_ln_f: f64) -> Self {
+        let num_bins = grid.num_bins;
+        Self {
+            grid,
+            ln_g: vec![0.0; num_bins],
+            histogram: vec![0; num_bins],
+            visited: vec![false; num_bins],
+            ln_f: 1.0,
+            final_ln_f,
+            flatness: 0.8,
+            schedule,
+            moves: 0,
+            stages: 0,
+            inverse_time: false,
+        }
+    }
+
+    fn num_visited(&self) -> usize {
+        self.visited.iter().filter(|&&v| v).count()
+    }
+
+    pub fn is_flat(&self) -> bool {
+        let counts: Vec<usize> = self
+            .histogram
+            .iter()
+            .zip(&self.visited)
+            .filter(|(_, &v)| v)
+            .map(|(&h, _)| h)
+            .collect();
+        if counts.is_empty() {
+            return false;
+        }
+        let mean = counts.iter().sum::<usize>() as f64 / counts.len() as f64;
+        counts.iter().all(|&h| h as f64 >= self.flatness * mean)
+    }
+
+    /// `moves` Wang–Landau steps without changing ln f.
+    pub fn walk<S: EnergyMoves>(&mut self, system: &mut S, moves: usize) {
+        let mut rng = rand::rng();
+        let mut energy = system.energy();
+        let mut bin = self
+            .grid
+            .bin(energy)
+            .expect("Initial energy outside the energy grid");
+        let mut num_visited = self.num_visited();
+        for _ in 0..moves {
+            let (mv, delta_e) = system.propose(&mut rng);
+            if let Some(new_bin) = self.grid.bin(energy + delta_e) {
+                let ln_ratio = self.ln_g[bin] - self.ln_g[new_bin];
+                if ln_ratio >= 0.0 || rng.random::<f64>() < ln_ratio.exp() {
+                    system.apply(mv);
+                    energy += delta_e;
+                    bin = new_bin;
+                }
+            }
+            self.ln_g[bin] += self.ln_f;
+            self.histogram[bin] += 1;
+            if !self.visited[bin] {
+                self.visited[bin] = true;
+                num_visited += 1;
+            }
+            self.moves += 1;
+            if self.inverse_time {
+                self.ln_f = num_visited as f64 / self.moves as f64;
+            }
+        }
+    }
+
+    /// Runs until ln f < `final_ln_f`, checking flatness every `check_interval` moves.
+    pub fn run<S: EnergyMoves>(&mut self, system: &mut S, check_interval: usize) {
+        while self.ln_f > self.final_ln_f {
+            self.walk(system, check_interval);
+            if self.inverse_time || !self.is_flat() {
+                continue;
+            }
+            self.stages += 1;
+            self.ln_f /= 2.0;
+            self.histogram.iter_mut().for_each(|h| *h = 0);
+            let one_over_t = self.num_visited() as f64 / self.moves as f64;
+            if self.schedule == Schedule::InverseTime && self.ln_f < one_over_t {
+                self.inverse_time = true;
+                self.ln_f = one_over_t;
+            }
+        }
+    }
+
+    /// ln g on the visited levels, shifted so the lowest visited level has ln g = 0.
+    pub fn density_of_states(&self) -> DensityOfStates {
+        let (energies, ln_g): (Vec<f64>, Vec<f64>) = (0..self.grid.num_bins)
+            .filter(|&k| self.visited[k])
+            .map(|k| (self.grid.energy(k), self.ln_g[k]))
+            .unzip();
+        let offset = ln_g[0];
+        DensityOfStates {
+            energies,
+            ln_g: ln_g.iter().map(|g| g - offset).collect(),
+        }
+    }
+}
+
+/// Density of states on discrete levels and the canonical thermodynamics it implies
+/// - Absolute values of F and S need `normalize` (e.g. Σ g = 2^N for Ising spins);
+///   U and C do not depend on the normalisation
+/// - C is the total heat capacity (Var E / T²); divide by N for per-spin values
+#[derive(Clone, Debug)]
+pub struct DensityOfStates {
+    pub energies: Vec<f64>,
+    pub ln_g: Vec<f64>,
+}
+
+impl DensityOfStates {
+    /// Shifts ln g so that Σ_E g(E) = exp(`ln_total`).
+    pub fn normalize(&mut self, ln_total: f64) {
+        let shift = ln_total - log_sum_exp(&self.ln_g);
+        self.ln_g.iter_mut().for_each(|g| *g += shift);
+    }
+
+    /// Canonical probabilities P(E) ∝ g(E) exp(-E/T).
+    pub fn canonical_distribution(&self, temperature: f64) -> Vec<f64> {
+        let exponents = self.boltzmann_exponents(temperature);
+        let ln_z = log_sum_exp(&exponents);
+        exponents.iter().map(|x| (x - ln_z).exp()).collect()
+    }
+
+    fn boltzmann_exponents(&self, temperature: f64) -> Vec<f64> {
+        self.ln_g
+            .iter()
+            .zip(&self.energies)
+            .map(|(g, e)| g - e / temperature)
+            .collect()
+    }
+
+    pub fn ln_partition_function(&self, temperature: f64) -> f64 {
+        log_sum_exp(&self.boltzmann_exponents(temperature))
+    }
+
+    pub fn free_energy(&self, temperature: f64) -> f64 {
+        -temperature * self.ln_partition_function(temperature)
+    }
+
+    pub fn mean_energy(&self, temperature: f64) -> f64 {
+        self.canonical_distribution(temperature)
+            .iter()
+            .zip(&self.energies)
+            .map(|(p, e)| p * e)
+            .sum()
+    }
+
+    pub fn specific_heat(&self, temperature: f64) -> f64 {
+        let p = self.canonical_distribution(temperature);
+        let mean: f64 = p.iter().zip(&self.energies).map(|(p, e)| p * e).sum();
+        let variance: f64 = p
+            .iter()
+            .zip(&self.energies)
+            .map(|(p, e)| p * (e - mean).powi(2))
+            .sum();
+        variance / (temperature * temperature)
+    }
+
+    /// S = (U - F) / T.
+    pub fn entropy(&self, temperature: f64) -> f64 {
+        (self.mean_energy(temperature) - self.free_energy(temperature)) / temperature
+    }
+}
+
+/// ln Σ exp(x_i) without overflow.
+pub fn log_sum_exp(xs: &[f64]) -> f64 {
+    let max = xs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    max + xs.iter().map(|x| (x - max).exp()).sum::<f64>().ln()
+}
+
+pub fn wang_landau_example() {
+    let mut model = IsingModel::new(8, 1.0);
+    let mut wl = WangLandau::new(EnergyGrid::ising(&model), Schedule::InverseTime, 1e-6);
+    wl.run(&mut model, 10_000);
+    let mut dos = wl.density_of_states();
+    dos.normalize(64.0 * 2f64.ln());
+    println!("Wang–Landau 8×8 Ising after {} moves:", wl.moves);
+    for t in [1.5, 2.0, 2.269, 2.5, 3.0] {
+        println!(
+            "T = {}: u = {:.4}, c = {:.4}, f = {:.4}, s = {:.4}",
+            t,
+            dos.mean_energy(t) / 64.0,
+            dos.specific_heat(t) / 64.0,
+            dos.free_energy(t) / 64.0,
+            dos.entropy(t) / 64.0
+        );
+    }
+}
+
+#[test]
+fn test_wang_landau_ising_4x4() {
+    // Exact g(E) of the 4×4 periodic Ising model for E = -32, -24, ..., 0 (symmetric).
+    let exact: [(f64, f64); 8] = [
+        (-32.0, 2.0),
+        (-24.0, 32.0),
+        (-20.0, 64.0),
+        (-16.0, 424.0),
+        (-12.0, 1728.0),
+        (-8.0, 6688.0),
+        (-4.0, 13568.0),
+        (0.0, 20524.0),
+    ];
+    let mut model = IsingModel::new(4, 1.0);
+    let mut wl = WangLandau::new(EnergyGrid::ising(&model), Schedule::InverseTime, 1e-5);
+    wl.run(&mut model, 1000);
+    let mut dos = wl.density_of_states();
+    dos.normalize(16.0 * 2f64.ln());
+    assert_eq!(dos.energies.len(), 15);
+    for (e, g) in exact {
+        let k = dos.energies.iter().position(|&x| x == e).unwrap();
+        assert!(
+            (dos.ln_g[k] - g.ln()).abs() < 0.1,
+            "ln g({}) = {}",
+            e,
+            dos.ln_g[k]
+        );
+    }
+
+    let exact_dos = DensityOfStates {
+        energies: exact
+            .iter()
+            .map(|x| x.0)
+            .chain(exact[..7].iter().rev().map(|x| -x.0))
+            .collect(),
+        ln_g: exact
+            .iter()
+            .map(|x| x.1.ln())
+            .chain(exact[..7].iter().rev().map(|x| x.1.ln()))
+            .collect(),
+    };
+    for t in [1.5, 2.5, 4.0] {
+        let c = dos.specific_heat(t);
+        let c_exact = exact_dos.specific_heat(t);
+        assert!(
+            (c - c_exact).abs() < 0.05 * c_exact,
+            "C({}) = {} vs {}",
+            t,
+            c,
+            c_exact
+        );
+        let f = dos.free_energy(t);
+        assert!((f - exact_dos.free_energy(t)).abs() < 0.2);
+    }
+}
+
+#[test]
+fn test_wang_landau_hamiltonian_chain() {
+    use crate::physics::hilbert::IsingHamiltonian;
+    // Open chain of 8 spins: E = -7 + 2k with g = 2 C(7, k).
+    let flip = |s: &Vec<i8>| {
+        let mut t = s.clone();
+        let i = rand::rng().random_range(0..t.len());
+        t[i] = -t[i];
+        t
+    };
+    let mut chain = HamiltonianReplica::new(
+        IsingHamiltonian { j: 1.0, h: 0.0 },
+        vec![1; 8],
+        1.0,
+        1,
+        flip,
+    );
+    let mut wl = WangLandau::new(EnergyGrid::new(-7.0, 7.0, 2.0), Schedule::InverseTime, 1e-5);
+    wl.run(&mut chain, 10_000);
+    let dos = wl.density_of_states();
+    let binomial = [1.0, 7.0, 21.0, 35.0, 35.0, 21.0, 7.0, 1.0];
+    for (k, c) in binomial.iter().enumerate() {
+        let ln_ratio = dos.ln_g[k] - dos.ln_g[0];
+        assert!(
+            (ln_ratio - f64::ln(*c)).abs() < 0.15,
+            "k = {}: {}",
+            k,
+            ln_ratio
+        );
+    }
+}
@@ -1,57 +1,73 @@
+use crate::models::lattice::{Boundary, Lattice};
 use rand::Rng;
 /// Percolation model
 /// - Simulates fluid flow through a grid.
-/// - Uses DFS to check if top connects to bottom.
+/// - Uses DFS over the lattice neighbour tables to check if top connects to bottom.
 /// - Example runs a random percolation test.
 pub struct Percolation {
     pub size: usize,
-    pub grid: Vec<Vec<bool>>,
+    pub lattice: Lattice,
+    pub sites: Vec<bool>,
 }
 
 impl Percolation {
+    /// Square L×L grid with open edges.
     pub fn new(size: usize) -> Self {
-        let grid = vec![vec![false; size]; size];
-        Self { size, grid }
+        Self::with_lattice(Lattice::square(size, Boundary::Open))
+    }
+
+    pub fn with_lattice(lattice: Lattice) -> Self {
+        let sites = vec![false; lattice.num_sites];
+        Self {
+            size: lattice.dims[0],
+            lattice,
+            sites,
+        }
     }
 
     pub fn open(&mut self, row: usize, col: usize) {
-        self.grid[row][col] = true;
+        self.sites[row * self.size + col] = true;
     }
 
     pub fn is_open(&self, row: usize, col: usize) -> bool {
-        self.grid[row][col]
+        self.sites[row * self.size + col]
     }
 
+    pub fn open_site(&mut self, site: usize) {
+        self.sites[site] = true;
+    }
+
+    /// Checks for an open path from the first to the last layer along the
+    /// lattice's last axis (top to bottom in 2D).
     pub fn percolates(&self) -> bool {
-        let mut visited = vec![vec![false; self.size]; self.size];
-        for col in 0..self.size {
-            if self.dfs(0, col, &mut visited) {
+        let axis = self.lattice.dimension() - 1;
+        let mut visited = vec![false; self.lattice.num_sites];
+        for site in 0..self.lattice.num_sites {
+            if self.lattice.coordinates(site)[axis] == 0 && self.dfs(site, &mut visited) {
                 return true;
             }
         }
         false
     }
 
-    fn dfs(&self, row: usize, col: usize, visited: &mut Vec<Vec<bool>>) -> bool {
-        if row >= self.size || col >= self.size || visited[row][col] || !self.is_open(row, col) {
+    fn dfs(&self, start: usize, visited: &mut [bool]) -> bool {
+        if visited[start] || !self.sites[start] {
             return false;
         }
-        visited[row][col] = true;
-        if row == self.size - 1 {
-            return true;
-        }
-        let directions = [(1, 0), (0, 1), (0, -1), (-1, 0)];
-        for &(dr, dc) in &directions {
-            let new_row = row as isize + dr;
-            let new_col = col as isize + dc;
-            if new_row >= 0
-                && new_row < self.size as isize
-                && new_col >= 0
-                && new_col < self.size as isize
-                && self.dfs(new_row as usize, new_col as usize, visited)
-            {
+        let axis = self.lattice.dimension() - 1;
+        let last = self.lattice.dims[axis] - 1;
+        visited[start] = true;
+        let mut stack = vec![start];
+        while let Some(site) = stack.pop() {
+            if self.lattice.coordinates(site)[axis] == last {
                 return true;
             }
+            for n in &self.lattice.neighbors[site] {
+                if !visited[n.site] && self.sites[n.site] {
+                    visited[n.site] = true;
+                    stack.push(n.site);
+                }
+            }
         }
         false
     }
@@ -86,3 +102,14 @@ fn test_percolation_with_multiple_paths() {
     }
     assert!(percolation.percolates(), "The system should percolate");
 }
+
+#[test]
+fn test_percolation_on_cubic_lattice() {
+    let mut percolation = Percolation::with_lattice(Lattice::simple_cubic(4, Boundary::Open));
+    // Open a straight column along the last axis.
+    for z in 0..4 {
+        let site = percolation.lattice.index(&[1, 2, z]);
+        percolation.open_site(site);
+    }
+    assert!(percolation.percolates(), "The column should percolate");
+}
@@ -38,8 +38,8 @@ fn build_stochastic_matrix(graph: &[Vec<usize>], num_pages: usize) -> Stochastic
     for (i, links) in graph.iter().enumerate() {
         if links.is_empty() {
             // Handle dead ends by linking to all pages
-            for j in 0..num_pages {
-                matrix[i][j] = 1.0 / num_pages as f64;
+            for entry in matrix[i].iter_mut() {
+                *entry = 1.0 / num_pages as f64;
             }
         } else {
             let prob = 1.0 / links.len() as f64;
@@ -11,6 +11,99 @@ pub fn autocorrelation(samples: &[f64], lag: usize) -> f64 {
     }
     cov / ((n - lag) as f64 * var)
 }
+
+/// A Monte Carlo estimate together with its one-sigma statistical error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub error: f64,
+}
+
+/// Integrated autocorrelation time τ_int = 1/2 + Σ_t ρ(t)
+/// - Uses Sokal's automatic window: the sum stops at the first lag W with W ≥ 6 τ_int(W)
+/// - Returns 0.5 for uncorrelated (or constant) data
+pub fn integrated_autocorrelation_time(samples: &[f64]) -> f64 {
+    let mut tau = 0.5;
+    for lag in 1..samples.len() / 2 {
+        tau += autocorrelation(samples, lag);
+        if lag as f64 >= 6.0 * tau {
+            break;
+        }
+    }
+    tau.max(0.5)
+}
+
+/// Sample mean with an error bar inflated by the autocorrelation:
+/// σ = sqrt(2 τ_int Var / n).
+pub fn mean_with_error(samples: &[f64]) -> Estimate {
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    if samples.len() < 2 {
+        return Estimate { mean, error: 0.0 };
+    }
+    let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let tau = integrated_autocorrelation_time(samples);
+    Estimate {
+        mean,
+        error: (2.0 * tau * var / n).sqrt(),
+    }
+}
+
+/// Number of jackknife blocks such that each block spans at least 2 τ_int of
+/// every series, capped at 50 and never below 2.
+pub fn jackknife_blocks(series: &[&[f64]]) -> usize {
+    let n = series.iter().map(|s| s.len()).min().unwrap_or(0);
+    let tau = series
+        .iter()
+        .map(|s| integrated_autocorrelation_time(s))
+        .fold(0.5, f64::max);
+    let block_len = (2.0 * tau).ceil() as usize;
+    (n / block_len.max(1)).clamp(2, 50)
+}
+
+/// Blocked jackknife estimate of a function of several series means
+/// - `series` are equally long time series (e.g. e, e², |m|, m²)
+/// - `f` receives the means of all series and returns the derived quantity
+pub fn jackknife<F>(series: &[&[f64]], num_blocks: usize, f: F) -> Estimate
+where
+    F: Fn(&[f64]) -> f64,
+{
+    let n = series.iter().map(|s| s.len()).min().unwrap_or(0);
+    let num_blocks = num_blocks.min(n);
+    assert!(num_blocks >= 2, "Jackknife needs at least two blocks");
+    let block_len = n / num_blocks;
+    let used = block_len * num_blocks;
+
+    let totals: Vec<f64> = series.iter().map(|s| s[..used].iter().sum()).collect();
+    let full_means: Vec<f64> = totals.iter().map(|t| t / used as f64).collect();
+    let mean = f(&full_means);
+
+    let leave_one_out: Vec<f64> = (0..num_blocks)
+        .map(|b| {
+            let means: Vec<f64> = series
+                .iter()
+                .zip(&totals)
+                .map(|(s, total)| {
+                    let block: f64 = s[b * block_len..(b + 1) * block_len].iter().sum();
+                    (total - block) / (used - block_len) as f64
+                })
+                .collect();
+            f(&means)
+        })
+        .collect();
+    let jack_mean = leave_one_out.iter().sum::<f64>() / num_blocks as f64;
+    let var = leave_one_out
+        .iter()
+        .map(|x| (x - jack_mean).powi(2))
+        .sum::<f64>()
+        * (num_blocks - 1) as f64
+        / num_blocks as f64;
+    Estimate {
+        mean,
+        error: var.sqrt(),
+    }
+}
+
 #[test]
 fn test_autocorrelation() {
     let samples = vec![1.0, 2.0, 3.0, 4.0, 5.0];
@@ -19,3 +112,29 @@ fn test_autocorrelation() {
     println!("Autocorrelation lag 1: {}", ac);
     assert!(ac > 0.0);
 }
+
+#[test]
+fn test_error_estimates() {
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let white: Vec<f64> = (0..10_000).map(|_| rng.random::<f64>()).collect();
+    assert!(integrated_autocorrelation_time(&white) < 1.0);
+
+    // An AR(1) process x_t = a x_{t-1} + noise has τ_int = (1 + a) / (2 (1 - a)).
+    let a: f64 = 0.9;
+    let mut x = 0.0;
+    let correlated: Vec<f64> = (0..50_000)
+        .map(|_| {
+            x = a * x + rng.random_range(-1.0..1.0);
+            x
+        })
+        .collect();
+    let tau = integrated_autocorrelation_time(&correlated);
+    assert!((tau - 9.5).abs() < 3.0, "tau_int = {}", tau);
+
+    // For a plain mean the jackknife reproduces the naive error of white noise.
+    let naive = mean_with_error(&white);
+    let jack = jackknife(&[&white], 50, |m| m[0]);
+    assert!((naive.mean - jack.mean).abs() < 1e-12);
+    assert!((naive.error / jack.error - 1.0).abs() < 0.5);
+}
@@ -4,10 +4,15 @@ pub mod algorithms {
     pub mod markov_chain;
     pub mod mc_estimator;
     pub mod metropolis;
+    pub mod multicanonical;
+    pub mod parallel_tempering;
+    pub mod reweighting;
     pub mod stochastic_matrix;
+    pub mod wang_landau;
 }
 
 pub mod models {
+    pub mod lattice;
     pub mod state;
 }
 pub mod applications {
@@ -18,9 +23,21 @@ pub mod applications {
 }
 pub mod physics {
     pub mod annealing;
+    pub mod blume_capel;
     pub mod brownian_motion;
+    pub mod coarsening;
+    pub mod correlations;
+    pub mod exact;
+    pub mod finite_size_scaling;
     pub mod hilbert;
     pub mod ising_model;
+    pub mod multispin_ising;
+    pub mod potts;
     pub mod quantum;
+    pub mod spin_glass;
+    pub mod stochastic_series;
+    pub mod transverse_ising;
+    pub mod vector_spin;
 }
 pub mod diagnostics;
+pub mod visualization;
@@ -1,14 +1,30 @@
 use monte_carlo::algorithms::distributions::{ProbabilityDistribution, RandomVariable};
 use monte_carlo::algorithms::gibbs::gibbs_example;
 use monte_carlo::algorithms::metropolis::metropolis_example;
+use monte_carlo::algorithms::multicanonical::multicanonical_example;
+use monte_carlo::algorithms::parallel_tempering::parallel_tempering_example;
+use monte_carlo::algorithms::reweighting::reweighting_example;
+use monte_carlo::algorithms::wang_landau::wang_landau_example;
 use monte_carlo::applications::percolation::percolation_example;
 use monte_carlo::applications::pi_estimation::estimate_pi;
 use monte_carlo::applications::random_walk::random_walk_example;
 use monte_carlo::applications::web_graph::web_graph;
 use monte_carlo::physics::annealing::simulated_annealing_example;
+use monte_carlo::physics::blume_capel::blume_capel_example;
 use monte_carlo::physics::brownian_motion::brownian_motion_example;
+use monte_carlo::physics::coarsening::coarsening_example;
+use monte_carlo::physics::correlations::correlations_example;
+use monte_carlo::physics::exact::exact_example;
+use monte_carlo::physics::finite_size_scaling::finite_size_scaling_example;
+use monte_carlo::physics::hilbert::exact_diagonalization_example;
 use monte_carlo::physics::ising_model::ising_example;
+use monte_carlo::physics::potts::potts_example;
 use monte_carlo::physics::quantum::quantum_monte_carlo_example;
+use monte_carlo::physics::spin_glass::spin_glass_example;
+use monte_carlo::physics::stochastic_series::stochastic_series_example;
+use monte_carlo::physics::transverse_ising::transverse_ising_example;
+use monte_carlo::physics::vector_spin::xy_example;
+use monte_carlo::visualization::visualization_example;
 fn main() {
     // Create a random variable representing a coin toss.
     let state_space = vec!["heads", "tails"];
@@ -45,13 +61,45 @@ fn main() {
 
     ising_example();
 
+    correlations_example();
+
+    coarsening_example();
+
+    finite_size_scaling_example();
+
+    exact_example();
+
+    potts_example();
+
+    blume_capel_example();
+
+    xy_example();
+
+    spin_glass_example();
+
+    parallel_tempering_example();
+
+    wang_landau_example();
+
+    multicanonical_example();
+
+    reweighting_example();
+
     random_walk_example();
 
     percolation_example();
 
+    visualization_example();
+
     simulated_annealing_example();
 
     brownian_motion_example();
 
     quantum_monte_carlo_example();
+
+    transverse_ising_example();
+
+    exact_diagonalization_example();
+
+    stochastic_series_example();
 }
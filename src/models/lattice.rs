@@ -0,0 +1,367 @@
+/// Lattice geometry shared by the lattice models
+/// - Sites are numbered 0..num_sites with axis 0 varying fastest, so on a 2D
+///   lattice site `row * L + col` sits at coordinates (col, row)
+/// - Every nearest-neighbour bond is stored once in `bonds` and twice in the
+///   per-site `neighbors` tables
+/// - Boundary conditions decide how bonds leaving the box are wired
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    /// Wrap around in every direction (torus).
+    Periodic,
+    /// Bonds leaving the box are dropped.
+    Open,
+    /// Sites are numbered linearly and a step along axis k moves by L^k modulo N.
+    Helical,
+    /// Periodic, but bonds wrapping around along axis 0 carry sign -1.
+    Antiperiodic,
+}
+
+/// Pairs treated as next-nearest neighbours by `Lattice::second_neighbor_bonds`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SecondNeighbors {
+    /// Face diagonals ±e_a ± e_b (the frustrated J1–J2 model).
+    Diagonal,
+    /// Distance two along one axis (the ANNNI model).
+    Axial(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LatticeKind {
+    Square,
+    /// Square lattice plus the (1, 1) diagonal, coordination 6.
+    Triangular,
+    /// Brick-wall representation: vertical bonds only on sites with even x + y.
+    Honeycomb,
+    SimpleCubic,
+    Hypercubic(usize),
+}
+
+/// An undirected bond from `a` to `b`, where `b` lies in the positive
+/// `direction` from `a`. `sign` is -1 for bonds crossing an antiperiodic boundary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bond {
+    pub a: usize,
+    pub b: usize,
+    pub direction: usize,
+    pub sign: i32,
+}
+
+/// Entry of a site's neighbour table; `bond` indexes into `Lattice::bonds`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Neighbor {
+    pub site: usize,
+    pub bond: usize,
+    pub sign: i32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Lattice {
+    pub kind: LatticeKind,
+    pub dims: Vec<usize>,
+    pub boundary: Boundary,
+    pub num_sites: usize,
+    pub bonds: Vec<Bond>,
+    pub neighbors: Vec<Vec<Neighbor>>,
+}
+
+impl Lattice {
+    /// Builds a lattice of the given kind with linear extent `size` along every axis.
+    pub fn new(kind: LatticeKind, size: usize, boundary: Boundary) -> Self {
+        let dimension = match kind {
+            LatticeKind::Square | LatticeKind::Triangular | LatticeKind::Honeycomb => 2,
+            LatticeKind::SimpleCubic => 3,
+            LatticeKind::Hypercubic(d) => d,
+        };
+        assert!(dimension >= 1, "Lattice needs at least one dimension");
+        assert!(size >= 2, "Lattice extent must be at least 2");
+        if kind == LatticeKind::Honeycomb && boundary != Boundary::Open {
+            assert!(
+                size.is_multiple_of(2),
+                "Honeycomb lattice needs an even extent"
+            );
+        }
+
+        let dims = vec![size; dimension];
+        let num_sites = size.pow(dimension as u32);
+        let mut lattice = Self {
+            kind,
+            dims,
+            boundary,
+            num_sites,
+            bonds: Vec::new(),
+            neighbors: vec![Vec::new(); num_sites],
+        };
+        lattice.build_bonds();
+        lattice
+    }
+
+    pub fn square(size: usize, boundary: Boundary) -> Self {
+        Self::new(LatticeKind::Square, size, boundary)
+    }
+
+    pub fn triangular(size: usize, boundary: Boundary) -> Self {
+        Self::new(LatticeKind::Triangular, size, boundary)
+    }
+
+    pub fn honeycomb(size: usize, boundary: Boundary) -> Self {
+        Self::new(LatticeKind::Honeycomb, size, boundary)
+    }
+
+    pub fn simple_cubic(size: usize, boundary: Boundary) -> Self {
+        Self::new(LatticeKind::SimpleCubic, size, boundary)
+    }
+
+    pub fn hypercubic(dimension: usize, size: usize, boundary: Boundary) -> Self {
+        Self::new(LatticeKind::Hypercubic(dimension), size, boundary)
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dims.len()
+    }
+
+    /// Number of distinct bond directions (the `direction` field ranges below this).
+    pub fn num_directions(&self) -> usize {
+        match self.kind {
+            LatticeKind::Triangular => 3,
+            _ => self.dimension(),
+        }
+    }
+
+    pub fn coordinates(&self, site: usize) -> Vec<usize> {
+        let mut rest = site;
+        self.dims
+            .iter()
+            .map(|&l| {
+                let x = rest % l;
+                rest /= l;
+                x
+            })
+            .collect()
+    }
+
+    pub fn index(&self, coords: &[usize]) -> usize {
+        let mut site = 0;
+        for (axis, &x) in coords.iter().enumerate().rev() {
+            site = site * self.dims[axis] + x;
+        }
+        site
+    }
+
+    /// Parity of the coordinate sum: the two checkerboard sublattices of a
+    /// bipartite lattice (square, cubic, honeycomb with even extents).
+    pub fn sublattice(&self, site: usize) -> usize {
+        let mut rest = site;
+        let mut sum = 0;
+        for &l in &self.dims {
+            sum += rest % l;
+            rest /= l;
+        }
+        sum % 2
+    }
+
+    /// True if every bond joins the two checkerboard sublattices, so all sites
+    /// of one sublattice can be updated independently.
+    pub fn is_bipartite(&self) -> bool {
+        self.bonds
+            .iter()
+            .all(|b| self.sublattice(b.a) != self.sublattice(b.b))
+    }
+
+    /// Labels the connected components formed by the bonds with `active[bond]`
+    /// set, returning the cluster index of every site and the cluster sizes.
+    pub fn label_clusters(&self, active: &[bool]) -> (Vec<usize>, Vec<usize>) {
+        let mut parent: Vec<usize> = (0..self.num_sites).collect();
+        for (bond, &on) in self.bonds.iter().zip(active) {
+            if on {
+                let ra = find_root(&mut parent, bond.a);
+                let rb = find_root(&mut parent, bond.b);
+                if ra != rb {
+                    parent[ra] = rb;
+                }
+            }
+        }
+
+        // Relabel roots as consecutive cluster indices.
+        let mut root_label = vec![usize::MAX; self.num_sites];
+        let mut labels = vec![0; self.num_sites];
+        let mut sizes = Vec::new();
+        for site in 0..self.num_sites {
+            let root = find_root(&mut parent, site);
+            if root_label[root] == usize::MAX {
+                root_label[root] = sizes.len();
+                sizes.push(0);
+            }
+            labels[site] = root_label[root];
+            sizes[labels[site]] += 1;
+        }
+        (labels, sizes)
+    }
+
+    /// Next-nearest-neighbour bonds of a square, cubic or hypercubic lattice, each
+    /// pair stored once with the same boundary wiring and signs as `bonds`;
+    /// `direction` numbers the offsets (for `Axial` it is always 0).
+    pub fn second_neighbor_bonds(&self, kind: SecondNeighbors) -> Vec<Bond> {
+        assert!(
+            matches!(
+                self.kind,
+                LatticeKind::Square | LatticeKind::SimpleCubic | LatticeKind::Hypercubic(_)
+            ),
+            "Second neighbours are defined for hypercubic lattices"
+        );
+        let d = self.dimension();
+        let offsets: Vec<Vec<isize>> = match kind {
+            SecondNeighbors::Diagonal => {
+                let mut offsets = Vec::new();
+                for a in 0..d {
+                    for b in a + 1..d {
+                        for db in [1, -1] {
+                            let mut v = vec![0; d];
+                            v[a] = 1;
+                            v[b] = db;
+                            offsets.push(v);
+                        }
+                    }
+                }
+                offsets
+            }
+            SecondNeighbors::Axial(axis) => {
+                assert!(axis < d, "Axis out of range");
+                let mut v = vec![0; d];
+                v[axis] = 2;
+                vec![v]
+            }
+        };
+        let mut bonds = Vec::new();
+        for a in 0..self.num_sites {
+            let coords = self.coordinates(a);
+            for (direction, offset) in offsets.iter().enumerate() {
+                if let Some((b, sign)) = self.target(a, &coords, offset) {
+                    assert!(b != a, "Lattice too small for second neighbours");
+                    bonds.push(Bond {
+                        a,
+                        b,
+                        direction,
+                        sign,
+                    });
+                }
+            }
+        }
+        bonds
+    }
+
+    /// Forward offsets of the nearest-neighbour bonds leaving a site.
+    fn offsets(&self, coords: &[usize]) -> Vec<Vec<isize>> {
+        let d = self.dimension();
+        let unit = |axis: usize| {
+            let mut v = vec![0; d];
+            v[axis] = 1;
+            v
+        };
+        match self.kind {
+            LatticeKind::Square | LatticeKind::SimpleCubic | LatticeKind::Hypercubic(_) => {
+                (0..d).map(unit).collect()
+            }
+            LatticeKind::Triangular => vec![unit(0), unit(1), vec![1, 1]],
+            LatticeKind::Honeycomb => {
+                if (coords[0] + coords[1]).is_multiple_of(2) {
+                    vec![unit(0), unit(1)]
+                } else {
+                    vec![unit(0)]
+                }
+            }
+        }
+    }
+
+    fn build_bonds(&mut self) {
+        for site in 0..self.num_sites {
+            let coords = self.coordinates(site);
+            for (direction, offset) in self.offsets(&coords).into_iter().enumerate() {
+                if let Some((b, sign)) = self.target(site, &coords, &offset) {
+                    self.add_bond(site, b, direction, sign);
+                }
+            }
+        }
+    }
+
+    fn target(&self, site: usize, coords: &[usize], offset: &[isize]) -> Option<(usize, i32)> {
+        if self.boundary == Boundary::Helical {
+            let mut stride = 1;
+            let mut shift = 0;
+            for (axis, &dx) in offset.iter().enumerate() {
+                shift += dx * stride as isize;
+                stride *= self.dims[axis];
+            }
+            let n = self.num_sites as isize;
+            return Some((
+                ((site as isize + shift) % n + n) as usize % self.num_sites,
+                1,
+            ));
+        }
+
+        let mut target = Vec::with_capacity(coords.len());
+        let mut sign = 1;
+        for (axis, (&x, &dx)) in coords.iter().zip(offset).enumerate() {
+            let l = self.dims[axis] as isize;
+            let moved = x as isize + dx;
+            if moved < 0 || moved >= l {
+                match self.boundary {
+                    Boundary::Open => return None,
+                    Boundary::Antiperiodic if axis == 0 => sign = -sign,
+                    _ => {}
+                }
+            }
+            target.push(((moved % l + l) % l) as usize);
+        }
+        Some((self.index(&target), sign))
+    }
+
+    fn add_bond(&mut self, a: usize, b: usize, direction: usize, sign: i32) {
+        let bond = self.bonds.len();
+        self.bonds.push(Bond {
+            a,
+            b,
+            direction,
+            sign,
+        });
+        self.neighbors[a].push(Neighbor {
+            site: b,
+            bond,
+            sign,
+        });
+        self.neighbors[b].push(Neighbor {
+            site: a,
+            bond,
+            sign,
+        });
+    }
+}
+
+fn find_root(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+#[test]
+fn test_lattice_coordination() {
+    let cases = [
+        (Lattice::square(4, Boundary::Periodic), 4),
+        (Lattice::triangular(4, Boundary::Periodic), 6),
+        (Lattice::honeycomb(4, Boundary::Periodic), 3),
+        (Lattice::simple_cubic(4, Boundary::Helical), 6),
+        (Lattice::hypercubic(4, 3, Boundary::Antiperiodic), 8),
+    ];
+    for (lattice, z) in cases {
+        assert!(lattice.neighbors.iter().all(|n| n.len() == z));
+        assert_eq!(lattice.bonds.len(), lattice.num_sites * z / 2);
+    }
+
+    let open = Lattice::square(4, Boundary::Open);
+    assert_eq!(open.bonds.len(), 2 * 4 * 3);
+    assert_eq!(open.neighbors[0].len(), 2);
+
+    let anti = Lattice::square(4, Boundary::Antiperiodic);
+    assert_eq!(anti.bonds.iter().filter(|b| b.sign < 0).count(), 4);
+}
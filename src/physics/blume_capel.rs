@@ -0,0 +1,290 @@
+use crate::algorithms::parallel_tempering::Replica;
+use crate::diagnostics::{jackknife, jackknife_blocks, mean_with_error, Estimate};
+use crate::models::lattice::{Boundary, Lattice};
+use crate::physics::ising_model::IsingObservables;
+use rand::Rng;
+
+/// Spin-1 Blume–Capel model, H = -J Σ_<ij> s_i s_j + D Σ_i s_i² with s_i ∈ {-1, 0, +1}
+/// - `crystal_field` D > 0 favours vacancies (s = 0); on the square lattice the
+///   transition turns first order beyond the tricritical point D_t ≈ 1.966 J, T_t ≈ 0.608 J
+/// - Metropolis and heat-bath single-site updates
+/// - Quadrupole moment q = (1/N) Σ s_i² is the density of occupied sites, 1 - q the vacancy density
+pub struct BlumeCapelModel {
+    pub coupling: f64,
+    pub crystal_field: f64,
+    pub temperature: f64,
+    pub lattice: Lattice,
+    pub spins: Vec<i32>,
+}
+
+/// Measurement phase of a `BlumeCapelModel`
+/// - `thermo` holds energy / magnetisation statistics in the same form as the Ising model
+/// - `quadrupole_susceptibility` is N Var(q) / T, which peaks along the transition line
+pub struct BlumeCapelObservables {
+    pub thermo: IsingObservables,
+    pub quadrupole: Estimate,
+    pub vacancy_density: Estimate,
+    pub quadrupole_susceptibility: Estimate,
+    pub quadrupole_series: Vec<f64>,
+}
+
+impl BlumeCapelModel {
+    /// Square L×L lattice with periodic boundaries.
+    pub fn new(size: usize, coupling: f64, crystal_field: f64, temperature: f64) -> Self {
+        Self::with_lattice(
+            Lattice::square(size, Boundary::Periodic),
+            coupling,
+            crystal_field,
+            temperature,
+        )
+    }
+
+    pub fn with_lattice(
+        lattice: Lattice,
+        coupling: f64,
+        crystal_field: f64,
+        temperature: f64,
+    ) -> Self {
+        let mut rng = rand::rng();
+        let spins = (0..lattice.num_sites)
+            .map(|_| rng.random_range(-1..=1))
+            .collect();
+        Self {
+            coupling,
+            crystal_field,
+            temperature,
+            lattice,
+            spins,
+        }
+    }
+
+    pub fn num_spins(&self) -> usize {
+        self.lattice.num_sites
+    }
+
+    /// Sum of the (sign-carrying) neighbour spins of `site`.
+    pub fn local_field(&self, site: usize) -> i32 {
+        self.lattice.neighbors[site]
+            .iter()
+            .map(|n| n.sign * self.spins[n.site])
+            .sum()
+    }
+
+    /// Energy change of setting `site` to `new`.
+    pub fn delta_energy(&self, site: usize, new: i32) -> f64 {
+        let old = self.spins[site];
+        -self.coupling * ((new - old) * self.local_field(site)) as f64
+            + self.crystal_field * (new * new - old * old) as f64
+    }
+
+    /// N random-site Metropolis moves, each proposing one of the two other states.
+    pub fn metropolis_sweep(&mut self) {
+        let mut rng = rand::rng();
+        for _ in 0..self.num_spins() {
+            let site = rng.random_range(0..self.num_spins());
+            // Shifting by 1 or 2 (mod 3) on {-1, 0, 1} reaches each other state once.
+            let new = (self.spins[site] + 1 + rng.random_range(1..3)) % 3 - 1;
+            let delta_e = self.delta_energy(site, new);
+            if delta_e <= 0.0 || rng.random::<f64>() < (-delta_e / self.temperature).exp() {
+                self.spins[site] = new;
+            }
+        }
+    }
+
+    /// N random-site heat-bath moves: the new state s is drawn with weight
+    /// exp(-(D s² - J s h) / T), h the neighbour sum, independent of the old one.
+    pub fn heat_bath_sweep(&mut self) {
+        let mut rng = rand::rng();
+        for _ in 0..self.num_spins() {
+            let site = rng.random_range(0..self.num_spins());
+            let h = self.local_field(site) as f64;
+            let energies = [
+                0.0,
+                self.crystal_field - self.coupling * h,
+                self.crystal_field + self.coupling * h,
+            ];
+            // Shift by the lowest level so the weights stay finite at low T.
+            let lowest = energies.iter().copied().fold(f64::INFINITY, f64::min);
+            let weights = energies.map(|e| (-(e - lowest) / self.temperature).exp());
+            let r = rng.random::<f64>() * weights.iter().sum::<f64>();
+            self.spins[site] = if r < weights[0] {
+                0
+            } else if r < weights[0] + weights[1] {
+                1
+            } else {
+                -1
+            };
+        }
+    }
+
+    pub fn energy(&self) -> f64 {
+        let bonds: i32 = self
+            .lattice
+            .bonds
+            .iter()
+            .map(|b| b.sign * self.spins[b.a] * self.spins[b.b])
+            .sum();
+        let occupied = self.spins.iter().filter(|&&s| s != 0).count();
+        -self.coupling * bonds as f64 + self.crystal_field * occupied as f64
+    }
+
+    pub fn energy_per_spin(&self) -> f64 {
+        self.energy() / self.num_spins() as f64
+    }
+
+    pub fn magnetization(&self) -> f64 {
+        self.spins.iter().sum::<i32>() as f64 / self.num_spins() as f64
+    }
+
+    /// q = (1/N) Σ s_i².
+    pub fn quadrupole(&self) -> f64 {
+        self.spins.iter().filter(|&&s| s != 0).count() as f64 / self.num_spins() as f64
+    }
+
+    /// Runs `thermalization` updates, then records energy, magnetisation and
+    /// quadrupole moment after each of `measurements` further updates.
+    pub fn measure_with<F>(
+        &mut self,
+        thermalization: usize,
+        measurements: usize,
+        mut update: F,
+    ) -> BlumeCapelObservables
+    where
+        F: FnMut(&mut Self),
+    {
+        for _ in 0..thermalization {
+            update(self);
+        }
+        let mut energies = Vec::with_capacity(measurements);
+        let mut magnetizations = Vec::with_capacity(measurements);
+        let mut quadrupoles = Vec::with_capacity(measurements);
+        for _ in 0..measurements {
+            update(self);
+            energies.push(self.energy_per_spin());
+            magnetizations.push(self.magnetization());
+            quadrupoles.push(self.quadrupole());
+        }
+
+        let n = self.num_spins() as f64;
+        let t = self.temperature;
+        let q2: Vec<f64> = quadrupoles.iter().map(|q| q * q).collect();
+        let quadrupole_susceptibility = jackknife(
+            &[&quadrupoles, &q2],
+            jackknife_blocks(&[&quadrupoles]),
+            |x| n * (x[1] - x[0] * x[0]) / t,
+        );
+        let vacancies: Vec<f64> = quadrupoles.iter().map(|q| 1.0 - q).collect();
+        BlumeCapelObservables {
+            thermo: IsingObservables::from_series(t, self.num_spins(), energies, magnetizations),
+            quadrupole: mean_with_error(&quadrupoles),
+            vacancy_density: mean_with_error(&vacancies),
+            quadrupole_susceptibility,
+            quadrupole_series: quadrupoles,
+        }
+    }
+}
+
+impl Replica for BlumeCapelModel {
+    fn energy(&self) -> f64 {
+        BlumeCapelModel::energy(self)
+    }
+    fn temperature(&self) -> f64 {
+        self.temperature
+    }
+    fn set_temperature(&mut self, temperature: f64) {
+        self.temperature = temperature;
+    }
+    fn sweep(&mut self) {
+        self.heat_bath_sweep();
+    }
+}
+
+pub fn blume_capel_example() {
+    // Crossing the transition line at fixed T = 0.5, below the tricritical temperature.
+    for d in [1.9, 1.95, 2.0] {
+        let mut model = BlumeCapelModel::new(16, 1.0, d, 0.5);
+        let obs = model.measure_with(500, 2000, |m| m.heat_bath_sweep());
+        println!(
+            "Blume–Capel D = {:.2}: |m| = {:.4} ± {:.4}, vacancies = {:.4} ± {:.4}",
+            d,
+            obs.thermo.abs_magnetization.mean,
+            obs.thermo.abs_magnetization.error,
+            obs.vacancy_density.mean,
+            obs.vacancy_density.error
+        );
+    }
+}
+
+#[test]
+fn test_blume_capel_updates_agree() {
+    let mut rng = rand::rng();
+    let mut model = BlumeCapelModel::new(6, 1.0, 0.7, 1.0);
+    for _ in 0..50 {
+        let site = rng.random_range(0..36);
+        let new = rng.random_range(-1..=1);
+        let before = model.energy();
+        let delta_e = model.delta_energy(site, new);
+        model.spins[site] = new;
+        assert!((model.energy() - before - delta_e).abs() < 1e-12);
+    }
+
+    // D = 0.5, T = 2.0 lies in the disordered phase: both updates sample the same ensemble.
+    let metropolis =
+        BlumeCapelModel::new(8, 1.0, 0.5, 2.0).measure_with(200, 3000, |m| m.metropolis_sweep());
+    let heat_bath =
+        BlumeCapelModel::new(8, 1.0, 0.5, 2.0).measure_with(200, 3000, |m| m.heat_bath_sweep());
+    for (a, b) in [
+        (metropolis.thermo.energy, heat_bath.thermo.energy),
+        (metropolis.quadrupole, heat_bath.quadrupole),
+    ] {
+        let sigma = (a.error.powi(2) + b.error.powi(2)).sqrt();
+        assert!(
+            (a.mean - b.mean).abs() < 5.0 * sigma + 1e-3,
+            "{} vs {}",
+            a.mean,
+            b.mean
+        );
+    }
+}
+
+#[test]
+fn test_blume_capel_phases() {
+    // Small D, low T: ordered with almost no vacancies.
+    let mut model = BlumeCapelModel::new(8, 1.0, 0.5, 0.4);
+    model.spins = vec![1; 64];
+    let obs = model.measure_with(200, 500, |m| m.heat_bath_sweep());
+    assert!(obs.thermo.abs_magnetization.mean > 0.95);
+    assert!(obs.vacancy_density.mean < 0.05);
+
+    // D beyond the ground-state boundary D = zJ/2 = 2: vacancies win.
+    let obs =
+        BlumeCapelModel::new(8, 1.0, 2.5, 0.4).measure_with(200, 500, |m| m.heat_bath_sweep());
+    assert!(obs.vacancy_density.mean > 0.9);
+    assert!((obs.quadrupole.mean + obs.vacancy_density.mean - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_blume_capel_sweep_and_reweighting() {
+    use crate::algorithms::reweighting::{HistogramRun, MultiHistogram};
+    use crate::physics::finite_size_scaling::TemperatureSweep;
+    let mut sweep = TemperatureSweep::new(vec![4, 6], vec![1.2, 1.6, 2.0], 100, 300);
+    sweep.run_with(
+        |size, t| BlumeCapelModel::new(size, 1.0, 1.0, t),
+        |model, t| model.temperature = t,
+        |model, thermalization, measurements| {
+            model
+                .measure_with(thermalization, measurements, |m| m.heat_bath_sweep())
+                .thermo
+        },
+    );
+    assert_eq!(sweep.results.len(), 2);
+    assert!(
+        sweep.results[1][0].abs_magnetization.mean > sweep.results[1][2].abs_magnetization.mean
+    );
+
+    // Reweighting a run to its own temperature reproduces the quadrupole moment.
+    let obs =
+        BlumeCapelModel::new(6, 1.0, 1.0, 1.5).measure_with(100, 500, |m| m.heat_bath_sweep());
+    let r = MultiHistogram::new(vec![HistogramRun::from_blume_capel(&obs)]).at(1.5);
+    assert!((r.observables[2].mean - obs.quadrupole.mean).abs() < 1e-9);
+}
@@ -0,0 +1,176 @@
+use crate::diagnostics::Estimate;
+use crate::models::lattice::Lattice;
+use crate::physics::finite_size_scaling::fit_power_law;
+use crate::physics::ising_model::{IsingModel, UpdateRule};
+
+/// Quench of `IsingModel` from T = ∞ to `temperature` with single-spin-flip dynamics
+/// - Every run starts from a fresh random (infinite-temperature) configuration
+/// - Observables are recorded at t = 0 and at roughly `points_per_decade` logarithmically
+///   spaced sweep counts up to `max_time`, then averaged over independent runs
+/// - `temperature` may be 0 (deep quench), where only moves with ΔE ≤ 0 are accepted
+pub struct Quench {
+    pub lattice: Lattice,
+    pub temperature: f64,
+    pub max_time: usize,
+    pub points_per_decade: usize,
+    pub update_rule: UpdateRule,
+}
+
+/// Run averages of a quench; entry i of each series belongs to `times[i]`.
+pub struct QuenchResult {
+    pub temperature: f64,
+    pub runs: usize,
+    pub times: Vec<usize>,
+    /// Energy per spin.
+    pub energy: Vec<Estimate>,
+    pub abs_magnetization: Vec<Estimate>,
+    pub magnetization_squared: Vec<Estimate>,
+    /// `IsingModel::domain_length`, bonds per broken bond.
+    pub domain_length: Vec<Estimate>,
+}
+
+impl Quench {
+    /// Metropolis dynamics, 10 measurement times per decade.
+    pub fn new(lattice: Lattice, temperature: f64, max_time: usize) -> Self {
+        assert!(temperature >= 0.0, "Temperature must be non-negative");
+        Self {
+            lattice,
+            temperature,
+            max_time,
+            points_per_decade: 10,
+            update_rule: UpdateRule::Metropolis,
+        }
+    }
+
+    /// Quench straight to T = 0.
+    pub fn deep(lattice: Lattice, max_time: usize) -> Self {
+        Self::new(lattice, 0.0, max_time)
+    }
+
+    /// 0 followed by the distinct values of round(10^(k / points_per_decade)) up to `max_time`.
+    pub fn times(&self) -> Vec<usize> {
+        let mut times = vec![0];
+        let mut k = 0;
+        loop {
+            let t = 10f64.powf(k as f64 / self.points_per_decade as f64).round() as usize;
+            if t > self.max_time {
+                break;
+            }
+            if t > *times.last().unwrap() {
+                times.push(t);
+            }
+            k += 1;
+        }
+        times
+    }
+
+    /// Averages `runs` independent quenches.
+    pub fn run(&self, runs: usize) -> QuenchResult {
+        assert!(runs >= 1, "Need at least one run");
+        let times = self.times();
+        // samples[observable][time][run]
+        let mut samples = vec![vec![Vec::with_capacity(runs); times.len()]; 4];
+        for _ in 0..runs {
+            let mut model = IsingModel::with_lattice(self.lattice.clone(), self.temperature);
+            model.update_rule = self.update_rule;
+            let mut t = 0;
+            for (i, &target) in times.iter().enumerate() {
+                while t < target {
+                    model.step();
+                    t += 1;
+                }
+                let m = model.magnetization();
+                samples[0][i].push(model.energy_per_spin());
+                samples[1][i].push(m.abs());
+                samples[2][i].push(m * m);
+                samples[3][i].push(model.domain_length());
+            }
+        }
+        let mut averaged = samples
+            .iter()
+            .map(|series| series.iter().map(|s| mean_over_runs(s)).collect());
+        QuenchResult {
+            temperature: self.temperature,
+            runs,
+            times,
+            energy: averaged.next().unwrap(),
+            abs_magnetization: averaged.next().unwrap(),
+            magnetization_squared: averaged.next().unwrap(),
+            domain_length: averaged.next().unwrap(),
+        }
+    }
+}
+
+/// Mean with the plain standard error; the runs are independent.
+fn mean_over_runs(values: &[f64]) -> Estimate {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return Estimate { mean, error: 0.0 };
+    }
+    let var = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    Estimate {
+        mean,
+        error: (var / n).sqrt(),
+    }
+}
+
+impl QuenchResult {
+    /// Exponent z⁻¹ of L(t) ~ t^(1/z) from a log-log fit over t_min ≤ t ≤ t_max;
+    /// the window should exclude the early transient and the finite-size saturation.
+    pub fn growth_exponent(&self, t_min: usize, t_max: usize) -> Estimate {
+        let (ts, ls): (Vec<f64>, Vec<f64>) = self
+            .times
+            .iter()
+            .zip(&self.domain_length)
+            .filter(|(&t, _)| t >= t_min.max(1) && t <= t_max)
+            .map(|(&t, l)| (t as f64, l.mean))
+            .unzip();
+        assert!(ts.len() >= 2, "Need at least two times in the fit window");
+        fit_power_law(&ts, &ls)
+    }
+}
+
+pub fn coarsening_example() {
+    use crate::models::lattice::Boundary;
+    let quench = Quench::new(Lattice::square(64, Boundary::Periodic), 1.5, 1000);
+    let result = quench.run(10);
+    for (i, t) in result.times.iter().enumerate().step_by(5) {
+        println!(
+            "t = {:5}: e = {:.4}, <m²> = {:.5}, L = {:.3} ± {:.3}",
+            t,
+            result.energy[i].mean,
+            result.magnetization_squared[i].mean,
+            result.domain_length[i].mean,
+            result.domain_length[i].error
+        );
+    }
+    // Curvature-driven (model A) coarsening has L ~ t^(1/2).
+    let exponent = result.growth_exponent(10, 1000);
+    println!(
+        "Growth exponent 1/z = {:.3} ± {:.3}",
+        exponent.mean, exponent.error
+    );
+}
+
+#[test]
+fn test_quench_coarsening() {
+    use crate::models::lattice::Boundary;
+    let quench = Quench::deep(Lattice::square(64, Boundary::Periodic), 200);
+    let times = quench.times();
+    assert_eq!(&times[..4], &[0, 1, 2, 3]);
+    assert!(times.windows(2).all(|w| w[0] < w[1]) && *times.last().unwrap() <= 200);
+
+    let result = quench.run(4);
+    // Infinite-temperature start, then monotone ordering at T = 0.
+    assert!(result.energy[0].mean.abs() < 0.1);
+    let last = result.times.len() - 1;
+    assert!(result.energy[last].mean < -1.5);
+    assert!(result.domain_length[last].mean > 4.0 * result.domain_length[1].mean);
+    let exponent = result.growth_exponent(10, 200);
+    assert!(
+        exponent.mean > 0.3 && exponent.mean < 0.7,
+        "1/z = {}",
+        exponent.mean
+    );
+}
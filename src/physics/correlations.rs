@@ -0,0 +1,338 @@
+use crate::diagnostics::{jackknife, jackknife_blocks, Estimate};
+use crate::models::lattice::{Boundary, Lattice};
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+/// Minimal complex number for the FFT.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    /// e^(iθ).
+    pub fn expi(theta: f64) -> Self {
+        Self::new(theta.cos(), theta.sin())
+    }
+
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    pub fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    pub fn scale(self, c: f64) -> Self {
+        Self::new(self.re * c, self.im * c)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, o: Complex) -> Complex {
+        Complex::new(self.re + o.re, self.im + o.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, o: Complex) -> Complex {
+        Complex::new(self.re - o.re, self.im - o.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, o: Complex) -> Complex {
+        Complex::new(
+            self.re * o.re - self.im * o.im,
+            self.re * o.im + self.im * o.re,
+        )
+    }
+}
+
+/// In-place discrete Fourier transform x̂_k = Σ_j x_j e^(∓2πi jk/n)
+/// - `inverse` flips the sign of the exponent; no 1/n normalisation is applied
+/// - Iterative radix-2 Cooley–Tukey for powers of two, plain O(n²) DFT otherwise
+pub fn fft(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+    if n <= 1 {
+        return;
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    if !n.is_power_of_two() {
+        let input = data.to_vec();
+        let step = sign * 2.0 * PI / n as f64;
+        for (k, out) in data.iter_mut().enumerate() {
+            *out = input
+                .iter()
+                .enumerate()
+                .map(|(j, &x)| x * Complex::expi(step * ((j * k) % n) as f64))
+                .fold(Complex::default(), |a, b| a + b);
+        }
+        return;
+    }
+
+    // Bit-reversal permutation.
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let root = Complex::expi(sign * 2.0 * PI / len as f64);
+        for start in (0..n).step_by(len) {
+            let mut w = Complex::new(1.0, 0.0);
+            for k in 0..len / 2 {
+                let even = data[start + k];
+                let odd = data[start + k + len / 2] * w;
+                data[start + k] = even + odd;
+                data[start + k + len / 2] = even - odd;
+                w = w * root;
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// Multidimensional transform of data stored with axis 0 fastest (the `Lattice`
+/// site order), transforming along every axis in turn.
+pub fn fft_nd(data: &mut [Complex], dims: &[usize], inverse: bool) {
+    assert_eq!(
+        data.len(),
+        dims.iter().product::<usize>(),
+        "Data must match dims"
+    );
+    let mut stride = 1;
+    for &len in dims {
+        let mut line = vec![Complex::default(); len];
+        for base in 0..data.len() {
+            // Visit every line along this axis once, from its first element.
+            if (base / stride) % len != 0 {
+                continue;
+            }
+            for (k, x) in line.iter_mut().enumerate() {
+                *x = data[base + k * stride];
+            }
+            fft(&mut line, inverse);
+            for (k, x) in line.iter().enumerate() {
+                data[base + k * stride] = *x;
+            }
+        }
+        stride *= len;
+    }
+}
+
+/// Spatial correlations accumulated over measurements on a periodic lattice
+/// - Each configuration is Fourier transformed once: S(k) = |ŝ(k)|² / N, and the
+///   full correlation G(r) = (1/N) Σ_i s_i s_i+r follows from the inverse FFT of S(k)
+/// - Wave vectors and displacements use the site index layout of the lattice
+///   (component n along axis a means k_a = 2πn / L_a)
+/// - G(r) along each axis, S(0) and S(k_min) are kept as time series, so the connected
+///   correlation and ξ carry blocked jackknife errors; the full S(k) keeps a naive error
+pub struct Correlations {
+    pub dims: Vec<usize>,
+    pub num_sites: usize,
+    pub measurements: usize,
+    /// `axis_series[a][r]`: time series of G(r e_a), r = 0..=L_a / 2.
+    axis_series: Vec<Vec<Vec<f64>>>,
+    magnetization_series: Vec<f64>,
+    s0_series: Vec<f64>,
+    s_min_series: Vec<f64>,
+    structure_sum: Vec<f64>,
+    structure_sum_sq: Vec<f64>,
+}
+
+impl Correlations {
+    pub fn new(lattice: &Lattice) -> Self {
+        assert!(
+            lattice.boundary == Boundary::Periodic,
+            "Correlation functions need periodic boundaries"
+        );
+        let dims = lattice.dims.clone();
+        Self {
+            axis_series: dims.iter().map(|&l| vec![Vec::new(); l / 2 + 1]).collect(),
+            num_sites: lattice.num_sites,
+            dims,
+            measurements: 0,
+            magnetization_series: Vec::new(),
+            s0_series: Vec::new(),
+            s_min_series: Vec::new(),
+            structure_sum: vec![0.0; lattice.num_sites],
+            structure_sum_sq: vec![0.0; lattice.num_sites],
+        }
+    }
+
+    /// Adds one configuration of site values (±1 for Ising spins).
+    pub fn add(&mut self, values: &[f64]) {
+        assert_eq!(values.len(), self.num_sites, "One value per site");
+        let n = self.num_sites as f64;
+        let mut data: Vec<Complex> = values.iter().map(|&s| Complex::new(s, 0.0)).collect();
+        fft_nd(&mut data, &self.dims, false);
+        let structure: Vec<f64> = data.iter().map(|z| z.norm_sqr() / n).collect();
+        for ((sum, sq), &s) in self
+            .structure_sum
+            .iter_mut()
+            .zip(self.structure_sum_sq.iter_mut())
+            .zip(&structure)
+        {
+            *sum += s;
+            *sq += s * s;
+        }
+
+        // G(r) = (1/N) IFFT[S(k)](r), by the Wiener–Khinchin theorem.
+        let mut correlation: Vec<Complex> =
+            structure.iter().map(|&s| Complex::new(s, 0.0)).collect();
+        fft_nd(&mut correlation, &self.dims, true);
+        let mut stride = 1;
+        for (axis, series) in self.axis_series.iter_mut().enumerate() {
+            for (r, s) in series.iter_mut().enumerate() {
+                s.push(correlation[r * stride].re / n);
+            }
+            stride *= self.dims[axis];
+        }
+
+        // S(k_min) averaged over the lattice axes.
+        let mut stride = 1;
+        let mut s_min = 0.0;
+        for &len in &self.dims {
+            s_min += structure[stride];
+            stride *= len;
+        }
+        self.s_min_series.push(s_min / self.dims.len() as f64);
+        self.s0_series.push(structure[0]);
+        self.magnetization_series
+            .push(values.iter().sum::<f64>() / n);
+        self.measurements += 1;
+    }
+
+    fn blocks(&self) -> usize {
+        jackknife_blocks(&[&self.s0_series, &self.magnetization_series])
+    }
+
+    /// Connected correlation G_c(r) = <s_i s_i+r> - <m>² along `axis`, r = 0..=L / 2.
+    pub fn correlation(&self, axis: usize) -> Vec<Estimate> {
+        let blocks = self.blocks();
+        self.axis_series[axis]
+            .iter()
+            .map(|g| {
+                jackknife(&[g, &self.magnetization_series], blocks, |x| {
+                    x[0] - x[1] * x[1]
+                })
+            })
+            .collect()
+    }
+
+    /// Mean S(k) for every wave vector (indexed like lattice sites) with the naive
+    /// standard error of the mean.
+    pub fn structure_factor(&self) -> Vec<Estimate> {
+        let m = self.measurements as f64;
+        self.structure_sum
+            .iter()
+            .zip(&self.structure_sum_sq)
+            .map(|(&sum, &sq)| {
+                let mean = sum / m;
+                let var = (sq / m - mean * mean).max(0.0);
+                Estimate {
+                    mean,
+                    error: (var / (m - 1.0).max(1.0)).sqrt(),
+                }
+            })
+            .collect()
+    }
+
+    /// Second-moment correlation length ξ = sqrt(S(0) / S(k_min) - 1) / (2 sin(k_min / 2)),
+    /// using the first axis length for k_min; meaningful in the disordered phase.
+    pub fn correlation_length(&self) -> Estimate {
+        let k_min = 2.0 * PI / self.dims[0] as f64;
+        let denominator = 2.0 * (k_min / 2.0).sin();
+        jackknife(&[&self.s0_series, &self.s_min_series], self.blocks(), |x| {
+            (x[0] / x[1] - 1.0).max(0.0).sqrt() / denominator
+        })
+    }
+}
+
+pub fn correlations_example() {
+    use crate::physics::ising_model::IsingModel;
+    for t in [2.4, 2.6, 3.0] {
+        let mut model = IsingModel::new(32, t);
+        let corr = model.measure_correlations(200, 1000, |m| {
+            m.wolff_sweep();
+        });
+        let xi = corr.correlation_length();
+        let g = corr.correlation(0);
+        println!(
+            "T = {}: ξ = {:.3} ± {:.3}, G(1) = {:.4}, G(4) = {:.4}",
+            t, xi.mean, xi.error, g[1].mean, g[4].mean
+        );
+    }
+}
+
+#[test]
+fn test_fft_matches_dft() {
+    use rand::Rng;
+    let mut rng = rand::rng();
+    for n in [8, 6] {
+        let input: Vec<Complex> = (0..n)
+            .map(|_| Complex::new(rng.random(), rng.random()))
+            .collect();
+        let mut fast = input.clone();
+        fft(&mut fast, false);
+        for (k, z) in fast.iter().enumerate() {
+            let direct = input
+                .iter()
+                .enumerate()
+                .map(|(j, &x)| x * Complex::expi(-2.0 * PI * (j * k) as f64 / n as f64))
+                .fold(Complex::default(), |a, b| a + b);
+            assert!((*z - direct).norm_sqr() < 1e-20);
+        }
+        fft(&mut fast, true);
+        for (a, b) in fast.iter().zip(&input) {
+            assert!((a.scale(1.0 / n as f64) - *b).norm_sqr() < 1e-20);
+        }
+    }
+}
+
+#[test]
+fn test_correlations_ising() {
+    use crate::physics::exact::onsager_energy;
+    use crate::physics::ising_model::IsingModel;
+    // A fully ordered state: G = 1 everywhere, G_c = 0, S concentrated at k = 0.
+    let lattice = Lattice::square(8, Boundary::Periodic);
+    let mut corr = Correlations::new(&lattice);
+    corr.add(&[1.0; 64]);
+    corr.add(&[1.0; 64]);
+    assert!((corr.structure_factor()[0].mean - 64.0).abs() < 1e-9);
+    assert!(corr.structure_factor()[1].mean.abs() < 1e-9);
+    assert!(corr.correlation(1).iter().all(|g| g.mean.abs() < 1e-9));
+
+    // Paramagnet: correlations decay with distance and ξ is a few lattice spacings.
+    let mut model = IsingModel::new(16, 3.0);
+    let corr = model.measure_correlations(100, 1000, |m| {
+        m.wolff_sweep();
+    });
+    let g = corr.correlation(0);
+    assert!((g[0].mean - 1.0).abs() < 0.05);
+    assert!(g[1].mean > g[3].mean && g[3].mean > g[8].mean - 5.0 * g[8].error);
+    // Nearest neighbours: G(1) = -e / 2, and ξ ≪ 16 leaves Onsager's e unshifted.
+    let nearest = -onsager_energy(3.0) / 2.0;
+    assert!(
+        (g[1].mean - nearest).abs() < 4.0 * g[1].error,
+        "G(1) = {} ± {}, Onsager {}",
+        g[1].mean,
+        g[1].error,
+        nearest
+    );
+    // Long Metropolis runs give ξ = 2.1 ± 0.1.
+    let xi = corr.correlation_length();
+    assert!(xi.mean > 1.5 && xi.mean < 2.8, "ξ = {}", xi.mean);
+}
@@ -0,0 +1,396 @@
+use crate::models::lattice::Lattice;
+use crate::physics::hilbert::IsingHamiltonian;
+use std::f64::consts::PI;
+
+/// Critical temperature of the square-lattice Ising model, 2J / ln(1 + √2).
+pub const SQUARE_LATTICE_TC: f64 = 2.269_185_314_213_022;
+
+/// Per-spin thermodynamics of a finite open chain (from `ChainTransferMatrix`).
+#[derive(Clone, Copy, Debug)]
+pub struct ChainThermodynamics {
+    pub temperature: f64,
+    pub free_energy: f64,
+    pub energy: f64,
+    pub specific_heat: f64,
+    pub magnetization: f64,
+    /// ∂m/∂h = N Var(m) / T.
+    pub susceptibility: f64,
+}
+
+/// Per-spin thermodynamics of a small lattice (from `ExactEnumeration`), in the
+/// conventions of `IsingObservables`.
+#[derive(Clone, Copy, Debug)]
+pub struct ExactObservables {
+    pub temperature: f64,
+    pub free_energy: f64,
+    pub energy: f64,
+    pub specific_heat: f64,
+    pub abs_magnetization: f64,
+    pub magnetization_squared: f64,
+    /// N (<m²> - <|m|>²) / T.
+    pub susceptibility: f64,
+    pub binder_cumulant: f64,
+}
+
+/// Value with first and second derivative with respect to one parameter.
+#[derive(Clone, Copy)]
+struct Jet {
+    v: f64,
+    d: f64,
+    dd: f64,
+}
+
+impl Jet {
+    const ZERO: Jet = Jet {
+        v: 0.0,
+        d: 0.0,
+        dd: 0.0,
+    };
+
+    /// exp(x a) and its derivatives with respect to x, given the value w = exp(x a).
+    fn exponential(w: f64, a: f64) -> Self {
+        Jet {
+            v: w,
+            d: a * w,
+            dd: a * a * w,
+        }
+    }
+
+    fn mul(self, o: Jet) -> Jet {
+        Jet {
+            v: self.v * o.v,
+            d: self.v * o.d + self.d * o.v,
+            dd: self.v * o.dd + 2.0 * self.d * o.d + self.dd * o.v,
+        }
+    }
+
+    fn add(self, o: Jet) -> Jet {
+        Jet {
+            v: self.v + o.v,
+            d: self.d + o.d,
+            dd: self.dd + o.dd,
+        }
+    }
+
+    fn scale(self, c: f64) -> Jet {
+        Jet {
+            v: self.v * c,
+            d: self.d * c,
+            dd: self.dd * c,
+        }
+    }
+}
+
+/// Exact transfer-matrix solution of the open chain described by `IsingHamiltonian`
+/// - H = -J Σ s_i s_i+1 - h Σ s_i on `num_spins` spins with free ends
+/// - Z = u^T T^(N-1) u with T(s, s') = exp(βJ s s' + βh (s + s') / 2), u(s) = exp(βh s / 2)
+/// - Energy, specific heat, magnetisation and susceptibility are exact derivatives of
+///   ln Z, carried through the matrix product rather than taken numerically
+pub struct ChainTransferMatrix {
+    pub j: f64,
+    pub h: f64,
+    pub num_spins: usize,
+}
+
+impl ChainTransferMatrix {
+    pub fn new(hamiltonian: &IsingHamiltonian, num_spins: usize) -> Self {
+        assert!(num_spins >= 1, "Chain needs at least one spin");
+        Self {
+            j: hamiltonian.j,
+            h: hamiltonian.h,
+            num_spins,
+        }
+    }
+
+    /// ln Z and its first two derivatives with respect to a parameter x of the
+    /// exponent; `bond_rate(s, s')` and `end_rate(s)` are the x-coefficients of the
+    /// exponents of T and u.
+    fn ln_z_jet<B, U>(&self, beta: f64, bond_rate: B, end_rate: U) -> (f64, f64, f64)
+    where
+        B: Fn(f64, f64) -> f64,
+        U: Fn(f64) -> f64,
+    {
+        let spins = [1.0, -1.0];
+        let end = |s: f64| Jet::exponential((beta * self.h * s / 2.0).exp(), end_rate(s));
+        let bond = |s: f64, t: f64| {
+            let exponent = beta * (self.j * s * t + self.h * (s + t) / 2.0);
+            Jet::exponential(exponent.exp(), bond_rate(s, t))
+        };
+        let mut vector = [end(1.0), end(-1.0)];
+        let mut ln_scale = 0.0;
+        for _ in 1..self.num_spins {
+            let mut next = [Jet::ZERO; 2];
+            for (k, &t) in spins.iter().enumerate() {
+                for (l, &s) in spins.iter().enumerate() {
+                    next[k] = next[k].add(vector[l].mul(bond(s, t)));
+                }
+            }
+            // Rescaling by a parameter-independent constant keeps d/v and dd/v intact.
+            let c = next[0].v.max(next[1].v);
+            ln_scale += c.ln();
+            vector = [next[0].scale(1.0 / c), next[1].scale(1.0 / c)];
+        }
+        let z = vector[0].mul(end(1.0)).add(vector[1].mul(end(-1.0)));
+        let first = z.d / z.v;
+        (z.v.ln() + ln_scale, first, z.dd / z.v - first * first)
+    }
+
+    pub fn at(&self, temperature: f64) -> ChainThermodynamics {
+        let beta = 1.0 / temperature;
+        let n = self.num_spins as f64;
+        // Derivatives in β give -<E> and Var E; derivatives in βh give <M> and Var M.
+        let (ln_z, minus_energy, energy_variance) = self.ln_z_jet(
+            beta,
+            |s, t| self.j * s * t + self.h * (s + t) / 2.0,
+            |s| self.h * s / 2.0,
+        );
+        let (_, magnetization, magnetization_variance) =
+            self.ln_z_jet(beta, |s, t| (s + t) / 2.0, |s| s / 2.0);
+        ChainThermodynamics {
+            temperature,
+            free_energy: -temperature * ln_z / n,
+            energy: -minus_energy / n,
+            specific_heat: beta * beta * energy_variance / n,
+            magnetization: magnetization / n,
+            susceptibility: beta * magnetization_variance / n,
+        }
+    }
+}
+
+/// Exact enumeration of all 2^N configurations of an Ising model on a small lattice
+/// - Visits configurations in Gray-code order (one spin flip per step) and
+///   accumulates the joint density of states g(E, M); practical up to N ≈ 25
+/// - Uses the bond signs of the lattice, so antiperiodic boundaries are supported
+pub struct ExactEnumeration {
+    pub num_sites: usize,
+    pub num_bonds: usize,
+    /// `counts[(E + B) / 2][(M + N) / 2]` for B bonds and N sites.
+    pub counts: Vec<Vec<u64>>,
+}
+
+impl ExactEnumeration {
+    pub fn new(lattice: &Lattice) -> Self {
+        let n = lattice.num_sites;
+        assert!(n <= 30, "Exact enumeration is limited to 30 sites");
+        let num_bonds = lattice.bonds.len();
+        let mut counts = vec![vec![0u64; n + 1]; num_bonds + 1];
+        let mut spins = vec![1i32; n];
+        let mut energy: i64 = -lattice.bonds.iter().map(|b| b.sign as i64).sum::<i64>();
+        let mut magnetization = n as i64;
+        let mut record = |energy: i64, magnetization: i64| {
+            let e = ((energy + num_bonds as i64) / 2) as usize;
+            counts[e][((magnetization + n as i64) / 2) as usize] += 1;
+            // Global spin flip leaves E unchanged and reverses M.
+            counts[e][((-magnetization + n as i64) / 2) as usize] += 1;
+        };
+        record(energy, magnetization);
+        // The last spin stays up; its mirror images come from the global flip.
+        for step in 1u64..1 << (n - 1) {
+            let site = step.trailing_zeros() as usize;
+            let field: i32 = lattice.neighbors[site]
+                .iter()
+                .map(|nb| nb.sign * spins[nb.site])
+                .sum();
+            energy += (2 * spins[site] * field) as i64;
+            magnetization -= 2 * spins[site] as i64;
+            spins[site] = -spins[site];
+            record(energy, magnetization);
+        }
+        Self {
+            num_sites: n,
+            num_bonds,
+            counts,
+        }
+    }
+
+    /// Marginal density of states as (E, g(E)) for every populated level.
+    pub fn density_of_states(&self) -> Vec<(f64, u64)> {
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(e, row)| (2.0 * e as f64 - self.num_bonds as f64, row.iter().sum()))
+            .filter(|&(_, g)| g > 0)
+            .collect()
+    }
+
+    pub fn at(&self, temperature: f64) -> ExactObservables {
+        let n = self.num_sites as f64;
+        let mut levels = Vec::new();
+        for (e, row) in self.counts.iter().enumerate() {
+            for (m, &g) in row.iter().enumerate() {
+                if g > 0 {
+                    let energy = 2.0 * e as f64 - self.num_bonds as f64;
+                    let magnetization = (2.0 * m as f64 - n) / n;
+                    levels.push((
+                        energy,
+                        magnetization,
+                        (g as f64).ln() - energy / temperature,
+                    ));
+                }
+            }
+        }
+        let max = levels.iter().map(|l| l.2).fold(f64::NEG_INFINITY, f64::max);
+        let (mut z, mut e1, mut e2, mut m1, mut m2, mut m4) = (0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        for &(energy, m, exponent) in &levels {
+            let w = (exponent - max).exp();
+            z += w;
+            e1 += w * energy;
+            e2 += w * energy * energy;
+            m1 += w * m.abs();
+            m2 += w * m * m;
+            m4 += w * m.powi(4);
+        }
+        let (e1, e2, m1, m2, m4) = (e1 / z, e2 / z, m1 / z, m2 / z, m4 / z);
+        ExactObservables {
+            temperature,
+            free_energy: -temperature * (max + z.ln()) / n,
+            energy: e1 / n,
+            specific_heat: (e2 - e1 * e1) / (temperature * temperature * n),
+            abs_magnetization: m1,
+            magnetization_squared: m2,
+            susceptibility: n * (m2 - m1 * m1) / temperature,
+            binder_cumulant: 1.0 - m4 / (3.0 * m2 * m2),
+        }
+    }
+}
+
+/// Arithmetic–geometric mean iteration returning (K(k), E(k)), the complete
+/// elliptic integrals of the first and second kind with modulus k < 1.
+fn elliptic_integrals(k: f64) -> (f64, f64) {
+    let (mut a, mut b) = (1.0, (1.0 - k * k).sqrt());
+    let mut sum = k * k / 2.0;
+    let mut power = 0.5;
+    while (a - b).abs() > 1e-15 * a {
+        let c = (a - b) / 2.0;
+        let next = ((a + b) / 2.0, (a * b).sqrt());
+        power *= 2.0;
+        sum += power * c * c;
+        a = next.0;
+        b = next.1;
+    }
+    let first = PI / (2.0 * a);
+    (first, first * (1.0 - sum))
+}
+
+/// Onsager modulus k = 2 sinh(2K) / cosh²(2K) with K = J / T (J = 1).
+fn onsager_modulus(temperature: f64) -> f64 {
+    let two_k = 2.0 / temperature;
+    2.0 * two_k.sinh() / two_k.cosh().powi(2)
+}
+
+/// Energy per spin of the infinite square-lattice Ising model (J = 1).
+pub fn onsager_energy(temperature: f64) -> f64 {
+    let two_k = 2.0 / temperature;
+    let t2 = two_k.tanh().powi(2);
+    let (k1, _) = elliptic_integrals(onsager_modulus(temperature));
+    -(1.0 / two_k.tanh()) * (1.0 + 2.0 / PI * (2.0 * t2 - 1.0) * k1)
+}
+
+/// Specific heat per spin of the infinite square-lattice Ising model; diverges at T_c.
+pub fn onsager_specific_heat(temperature: f64) -> f64 {
+    let k = 1.0 / temperature;
+    let two_k = 2.0 * k;
+    let t2 = two_k.tanh().powi(2);
+    let (k1, e1) = elliptic_integrals(onsager_modulus(temperature));
+    let prefactor = 4.0 / PI * (k / two_k.tanh()).powi(2);
+    prefactor * (k1 - e1 - (1.0 - t2) * (PI / 2.0 + (2.0 * t2 - 1.0) * k1))
+}
+
+/// Spontaneous magnetisation (1 - sinh⁻⁴(2J/T))^(1/8) below T_c, 0 above.
+pub fn onsager_magnetization(temperature: f64) -> f64 {
+    if temperature >= SQUARE_LATTICE_TC {
+        return 0.0;
+    }
+    (1.0 - (2.0 / temperature).sinh().powi(-4)).powf(0.125)
+}
+
+/// Free energy per spin, -T [ln(2 cosh 2K) + (1/π) ∫_0^π/2 ln((1 + √(1 - k² sin²θ)) / 2) dθ].
+pub fn onsager_free_energy(temperature: f64) -> f64 {
+    let k = onsager_modulus(temperature);
+    // Simpson's rule; the integrand is smooth for k < 1 and integrable at k = 1.
+    let steps = 2000;
+    let h = PI / 2.0 / steps as f64;
+    let f = |theta: f64| ((1.0 + (1.0 - (k * theta.sin()).powi(2)).sqrt()) / 2.0).ln();
+    let integral = (0..=steps)
+        .map(|i| {
+            let weight = if i == 0 || i == steps {
+                1.0
+            } else if i % 2 == 1 {
+                4.0
+            } else {
+                2.0
+            };
+            weight * f(i as f64 * h)
+        })
+        .sum::<f64>()
+        * h
+        / 3.0;
+    -temperature * ((2.0 * (2.0 / temperature).cosh()).ln() + integral / PI)
+}
+
+pub fn exact_example() {
+    for t in [1.5, 2.0, 2.5, 3.0] {
+        println!(
+            "Onsager T = {}: u = {:.6}, c = {:.6}, m = {:.6}, f = {:.6}",
+            t,
+            onsager_energy(t),
+            onsager_specific_heat(t),
+            onsager_magnetization(t),
+            onsager_free_energy(t)
+        );
+    }
+    let enumeration = ExactEnumeration::new(&Lattice::square(
+        4,
+        crate::models::lattice::Boundary::Periodic,
+    ));
+    let exact = enumeration.at(2.5);
+    println!(
+        "4×4 exact at T = 2.5: u = {:.6}, c = {:.6}, |m| = {:.6}",
+        exact.energy, exact.specific_heat, exact.abs_magnetization
+    );
+}
+
+#[test]
+fn test_exact_references_consistent() {
+    use crate::models::lattice::Boundary;
+    // Zero-field open chain: E/N = -(N - 1) tanh(βJ) / N.
+    let chain = ChainTransferMatrix::new(&IsingHamiltonian { j: 1.0, h: 0.0 }, 10).at(1.5);
+    assert!((chain.energy + 0.9 * (1.0f64 / 1.5).tanh()).abs() < 1e-12);
+    assert!(chain.magnetization.abs() < 1e-12);
+
+    // Chain with field against brute-force enumeration of the same Hamiltonian.
+    use crate::physics::hilbert::Hamiltonian;
+    let hamiltonian = IsingHamiltonian { j: 0.7, h: 0.3 };
+    let n = 6;
+    let t = 1.2;
+    let (mut z, mut e, mut m) = (0.0, 0.0, 0.0);
+    for config in 0..1u32 << n {
+        let state: Vec<i8> = (0..n)
+            .map(|i| if config >> i & 1 == 1 { 1 } else { -1 })
+            .collect();
+        let w = (-hamiltonian.energy(&state) / t).exp();
+        z += w;
+        e += w * hamiltonian.energy(&state);
+        m += w * state.iter().map(|&s| s as f64).sum::<f64>();
+    }
+    let chain = ChainTransferMatrix::new(&hamiltonian, n).at(t);
+    assert!((chain.energy - e / z / n as f64).abs() < 1e-12);
+    assert!((chain.magnetization - m / z / n as f64).abs() < 1e-12);
+    assert!((chain.free_energy + t * z.ln() / n as f64).abs() < 1e-12);
+
+    // 4×4 periodic: 2^16 states, g(-32) = 2, g(-24) = 32.
+    let enumeration = ExactEnumeration::new(&Lattice::square(4, Boundary::Periodic));
+    let dos = enumeration.density_of_states();
+    assert_eq!(dos.iter().map(|d| d.1).sum::<u64>(), 1 << 16);
+    assert_eq!(dos[0], (-32.0, 2));
+    assert_eq!(dos[1], (-24.0, 32));
+
+    // Onsager: internal energy at T_c is -√2, and -d(βf)/dβ = u.
+    assert!((onsager_energy(SQUARE_LATTICE_TC + 1e-9) + 2f64.sqrt()).abs() < 1e-6);
+    let (t, dt) = (2.0, 1e-4);
+    let beta_f = |t: f64| onsager_free_energy(t) / t;
+    let u = (beta_f(t + dt) - beta_f(t - dt)) / (1.0 / (t + dt) - 1.0 / (t - dt));
+    assert!((u - onsager_energy(t)).abs() < 1e-5);
+    let c = (onsager_energy(t + dt) - onsager_energy(t - dt)) / (2.0 * dt);
+    assert!((c - onsager_specific_heat(t)).abs() < 1e-5);
+}
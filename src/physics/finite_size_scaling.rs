@@ -0,0 +1,385 @@
+use crate::diagnostics::Estimate;
+use crate::physics::ising_model::{IsingModel, IsingObservables};
+use std::fmt::Write as _;
+
+/// Temperature sweep and finite-size scaling analysis
+/// - Runs `IsingModel` (or any model via `run_with`) for every lattice size
+///   over a grid of temperatures
+/// - Locates the Binder cumulant crossings to estimate T_c
+/// - Fits ν, β/ν and γ/ν by minimising the spread of scaling collapses
+/// - Produces whitespace-separated tables for plotting
+pub struct TemperatureSweep {
+    pub sizes: Vec<usize>,
+    pub temperatures: Vec<f64>,
+    pub thermalization: usize,
+    pub measurements: usize,
+    /// `results[size_index][temperature_index]`
+    pub results: Vec<Vec<IsingObservables>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Observable {
+    Energy,
+    AbsMagnetization,
+    SpecificHeat,
+    Susceptibility,
+    BinderCumulant,
+}
+
+impl Observable {
+    pub fn of(&self, obs: &IsingObservables) -> Estimate {
+        match self {
+            Observable::Energy => obs.energy,
+            Observable::AbsMagnetization => obs.abs_magnetization,
+            Observable::SpecificHeat => obs.specific_heat,
+            Observable::Susceptibility => obs.susceptibility,
+            Observable::BinderCumulant => obs.binder_cumulant,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CriticalExponents {
+    pub tc: f64,
+    pub nu: f64,
+    pub beta_over_nu: f64,
+    pub gamma_over_nu: f64,
+}
+
+impl TemperatureSweep {
+    pub fn new(
+        sizes: Vec<usize>,
+        temperatures: Vec<f64>,
+        thermalization: usize,
+        measurements: usize,
+    ) -> Self {
+        assert!(
+            temperatures.len() >= 2,
+            "Need at least two temperatures to locate crossings"
+        );
+        assert!(
+            temperatures.windows(2).all(|w| w[0] < w[1]),
+            "Temperatures must be strictly increasing"
+        );
+        Self {
+            sizes,
+            temperatures,
+            thermalization,
+            measurements,
+            results: Vec::new(),
+        }
+    }
+
+    /// Runs the sweep with Wolff updates (one sweep-equivalent per measurement).
+    /// Each size starts at the lowest temperature and carries its
+    /// configuration over to the next one.
+    pub fn run(&mut self) {
+        self.run_with(
+            IsingModel::new,
+            |model, t| model.temperature = t,
+            |model, thermalization, measurements| {
+                model.measure_with(thermalization, measurements, |m| {
+                    m.wolff_sweep();
+                })
+            },
+        );
+    }
+
+    /// Runs the sweep for any model: `create(size, T)` builds one system per size,
+    /// which `set_temperature(model, T)` carries up the temperature grid, and
+    /// `measure(model, thermalization, measurements)` returns its statistics there.
+    pub fn run_with<R, C, S, M>(&mut self, mut create: C, mut set_temperature: S, mut measure: M)
+    where
+        C: FnMut(usize, f64) -> R,
+        S: FnMut(&mut R, f64),
+        M: FnMut(&mut R, usize, usize) -> IsingObservables,
+    {
+        self.results = self
+            .sizes
+            .iter()
+            .map(|&size| {
+                let mut model = create(size, self.temperatures[0]);
+                self.temperatures
+                    .iter()
+                    .map(|&t| {
+                        set_temperature(&mut model, t);
+                        measure(&mut model, self.thermalization, self.measurements)
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    /// Linear interpolation of an observable of one lattice size at temperature `t`.
+    pub fn interpolate(&self, size_index: usize, observable: Observable, t: f64) -> f64 {
+        let values: Vec<f64> = self.results[size_index]
+            .iter()
+            .map(|o| observable.of(o).mean)
+            .collect();
+        interpolate(&self.temperatures, &values, t)
+    }
+
+    /// Temperatures where the Binder cumulants of consecutive sizes cross,
+    /// as `(smaller size, larger size, T_cross)`.
+    pub fn binder_crossings(&self) -> Vec<(usize, usize, f64)> {
+        let mut crossings = Vec::new();
+        for k in 0..self.sizes.len().saturating_sub(1) {
+            let diff: Vec<f64> = (0..self.temperatures.len())
+                .map(|i| {
+                    self.results[k][i].binder_cumulant.mean
+                        - self.results[k + 1][i].binder_cumulant.mean
+                })
+                .collect();
+            // Below T_c the larger lattice has the larger cumulant, above it the
+            // smaller one. Deep in the ordered phase both sit at 2/3 and the
+            // difference is pure noise, so take the highest-temperature crossing.
+            if let Some(i) = (0..diff.len() - 1).rfind(|&i| diff[i] < 0.0 && diff[i + 1] >= 0.0) {
+                let (t0, t1) = (self.temperatures[i], self.temperatures[i + 1]);
+                let t = t0 + (t1 - t0) * diff[i] / (diff[i] - diff[i + 1]);
+                crossings.push((self.sizes[k], self.sizes[k + 1], t));
+            }
+        }
+        crossings
+    }
+
+    /// Mean and spread of the Binder crossing temperatures.
+    pub fn estimate_critical_temperature(&self) -> Option<Estimate> {
+        let ts: Vec<f64> = self.binder_crossings().iter().map(|c| c.2).collect();
+        if ts.is_empty() {
+            return None;
+        }
+        let n = ts.len() as f64;
+        let mean = ts.iter().sum::<f64>() / n;
+        let error = if ts.len() > 1 {
+            (ts.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n * (n - 1.0))).sqrt()
+        } else {
+            0.0
+        };
+        Some(Estimate { mean, error })
+    }
+
+    /// Quality S of the collapse of Y L^(-exponent) against (T - T_c) L^(1/ν):
+    /// mean squared deviation between every point and the other sizes' curves
+    /// interpolated at the same scaling variable, relative to the mean of Y².
+    pub fn collapse_quality(&self, observable: Observable, tc: f64, nu: f64, exponent: f64) -> f64 {
+        let curves: Vec<(Vec<f64>, Vec<f64>)> = self
+            .sizes
+            .iter()
+            .enumerate()
+            .map(|(k, &size)| self.scaled_curve(k, size, observable, tc, nu, exponent))
+            .collect();
+
+        let mut sum = 0.0;
+        let mut norm = 0.0;
+        let mut count = 0;
+        for (k, (xs, ys)) in curves.iter().enumerate() {
+            for (&x, &y) in xs.iter().zip(ys) {
+                for (other, (oxs, oys)) in curves.iter().enumerate() {
+                    if other != k && x >= oxs[0] && x <= oxs[oxs.len() - 1] {
+                        sum += (y - interpolate(oxs, oys, x)).powi(2);
+                        norm += y * y;
+                        count += 1;
+                    }
+                }
+            }
+        }
+        if count == 0 {
+            return f64::INFINITY;
+        }
+        sum / norm
+    }
+
+    /// Finite-size scaling collapse fits
+    /// - ν from the Binder cumulant (no prefactor exponent)
+    /// - γ/ν from χ L^(-γ/ν) and β/ν from |m| L^(β/ν) at that ν
+    pub fn fit_exponents(&self, tc: f64) -> CriticalExponents {
+        let nu = minimize(0.3, 3.0, |nu| {
+            self.collapse_quality(Observable::BinderCumulant, tc, nu, 0.0)
+        });
+        let gamma_over_nu = minimize(0.0, 3.0, |g| {
+            self.collapse_quality(Observable::Susceptibility, tc, nu, g)
+        });
+        let beta_over_nu = minimize(0.0, 1.0, |b| {
+            self.collapse_quality(Observable::AbsMagnetization, tc, nu, -b)
+        });
+        CriticalExponents {
+            tc,
+            nu,
+            beta_over_nu,
+            gamma_over_nu,
+        }
+    }
+
+    /// One row per (L, T): `L T e de |m| d|m| C dC chi dchi U dU`.
+    pub fn table(&self) -> String {
+        let mut out = String::from("# L T e de |m| d|m| C dC chi dchi U dU\n");
+        let columns = [
+            Observable::Energy,
+            Observable::AbsMagnetization,
+            Observable::SpecificHeat,
+            Observable::Susceptibility,
+            Observable::BinderCumulant,
+        ];
+        for (k, &size) in self.sizes.iter().enumerate() {
+            for (i, &t) in self.temperatures.iter().enumerate() {
+                write!(out, "{} {:.6}", size, t).unwrap();
+                for column in columns {
+                    let e = column.of(&self.results[k][i]);
+                    write!(out, " {:.6} {:.6}", e.mean, e.error).unwrap();
+                }
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Scaled data `L x y` of one collapse, blocks separated by blank lines.
+    pub fn collapse_table(
+        &self,
+        observable: Observable,
+        tc: f64,
+        nu: f64,
+        exponent: f64,
+    ) -> String {
+        let mut out = String::from("# L (T-Tc)L^(1/nu) Y L^(-exponent)\n");
+        for (k, &size) in self.sizes.iter().enumerate() {
+            let (xs, ys) = self.scaled_curve(k, size, observable, tc, nu, exponent);
+            for (x, y) in xs.iter().zip(ys) {
+                writeln!(out, "{} {:.6} {:.6}", size, x, y).unwrap();
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn write_table(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.table())
+    }
+
+    fn scaled_curve(
+        &self,
+        size_index: usize,
+        size: usize,
+        observable: Observable,
+        tc: f64,
+        nu: f64,
+        exponent: f64,
+    ) -> (Vec<f64>, Vec<f64>) {
+        let l = size as f64;
+        let xs = self
+            .temperatures
+            .iter()
+            .map(|t| (t - tc) * l.powf(1.0 / nu))
+            .collect();
+        let ys = self.results[size_index]
+            .iter()
+            .map(|o| observable.of(o).mean * l.powf(-exponent))
+            .collect();
+        (xs, ys)
+    }
+}
+
+/// Least-squares fit of y = A x^p on log-log scale; returns p with its standard error.
+pub fn fit_power_law(xs: &[f64], ys: &[f64]) -> Estimate {
+    let n = xs.len() as f64;
+    let lx: Vec<f64> = xs.iter().map(|x| x.ln()).collect();
+    let ly: Vec<f64> = ys.iter().map(|y| y.abs().ln()).collect();
+    let mx = lx.iter().sum::<f64>() / n;
+    let my = ly.iter().sum::<f64>() / n;
+    let sxx: f64 = lx.iter().map(|x| (x - mx).powi(2)).sum();
+    let sxy: f64 = lx.iter().zip(&ly).map(|(x, y)| (x - mx) * (y - my)).sum();
+    let slope = sxy / sxx;
+    let residual: f64 = lx
+        .iter()
+        .zip(&ly)
+        .map(|(x, y)| (y - my - slope * (x - mx)).powi(2))
+        .sum();
+    let error = if xs.len() > 2 {
+        (residual / ((n - 2.0) * sxx)).sqrt()
+    } else {
+        0.0
+    };
+    Estimate { mean: slope, error }
+}
+
+fn interpolate(xs: &[f64], ys: &[f64], x: f64) -> f64 {
+    let i = match xs.iter().position(|&xi| xi >= x) {
+        Some(0) => 1,
+        Some(i) => i,
+        None => xs.len() - 1,
+    };
+    let (x0, x1) = (xs[i - 1], xs[i]);
+    ys[i - 1] + (ys[i] - ys[i - 1]) * (x - x0) / (x1 - x0)
+}
+
+/// Grid search followed by golden-section refinement on [lo, hi].
+fn minimize<F: Fn(f64) -> f64>(lo: f64, hi: f64, f: F) -> f64 {
+    let steps = 40;
+    let h = (hi - lo) / steps as f64;
+    let best = (0..=steps)
+        .map(|i| lo + i as f64 * h)
+        .min_by(|a, b| f(*a).total_cmp(&f(*b)))
+        .unwrap();
+    let (mut a, mut b) = ((best - h).max(lo), (best + h).min(hi));
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    for _ in 0..40 {
+        let c = b - ratio * (b - a);
+        let d = a + ratio * (b - a);
+        if f(c) < f(d) {
+            b = d;
+        } else {
+            a = c;
+        }
+    }
+    0.5 * (a + b)
+}
+
+pub fn finite_size_scaling_example() {
+    let temperatures: Vec<f64> = (0..9).map(|i| 2.1 + 0.04 * i as f64).collect();
+    let mut sweep = TemperatureSweep::new(vec![8, 16], temperatures, 100, 500);
+    sweep.run();
+    print!("{}", sweep.table());
+    if let Some(tc) = sweep.estimate_critical_temperature() {
+        println!("Binder crossing T_c: {:.4} ± {:.4}", tc.mean, tc.error);
+        let exponents = sweep.fit_exponents(tc.mean);
+        println!("Collapse exponents: {:?}", exponents);
+    }
+}
+
+#[test]
+fn test_binder_crossing_near_onsager() {
+    let temperatures: Vec<f64> = (0..9).map(|i| 2.0 + 0.07 * i as f64).collect();
+    let mut sweep = TemperatureSweep::new(vec![8, 16], temperatures, 200, 4000);
+    sweep.run();
+    let tc = sweep.estimate_critical_temperature().expect("no crossing");
+    // The L = 8, 16 crossing sits about 0.015 below T_c with a spread of 0.01.
+    assert!((tc.mean - 2.269).abs() < 0.05, "T_c = {}", tc.mean);
+
+    let exponents = sweep.fit_exponents(2.269);
+    // 2D Ising: ν = 1, γ/ν = 7/4, β/ν = 1/8. With L = 8, 16 the collapses carry
+    // corrections to scaling (repeated runs give ν = 0.94 ± 0.04, γ/ν = 1.77 ± 0.03,
+    // β/ν = 0.121 ± 0.004), so allow ±0.2, ±0.12 and ±0.02 (mean field would give
+    // ν = 1/2, γ/ν = 2, β/ν = 1).
+    assert!((exponents.nu - 1.0).abs() < 0.2, "ν = {}", exponents.nu);
+    assert!(
+        (exponents.gamma_over_nu - 1.75).abs() < 0.12,
+        "γ/ν = {}",
+        exponents.gamma_over_nu
+    );
+    assert!(
+        (exponents.beta_over_nu - 0.125).abs() < 0.02,
+        "β/ν = {}",
+        exponents.beta_over_nu
+    );
+    assert_eq!(sweep.table().lines().count(), 1 + 2 * (9 + 1));
+}
+
+#[test]
+fn test_fit_power_law() {
+    let xs = [4.0, 8.0, 16.0, 32.0];
+    let ys: Vec<f64> = xs.iter().map(|x: &f64| 0.7 * x.powf(1.75)).collect();
+    let fit = fit_power_law(&xs, &ys);
+    assert!((fit.mean - 1.75).abs() < 1e-10);
+    assert!(fit.error < 1e-8);
+}
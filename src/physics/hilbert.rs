@@ -1,3 +1,7 @@
+use crate::models::lattice::{Boundary, Lattice};
+use crate::physics::correlations::Complex;
+use rand::Rng;
+
 /// Trait for Hamiltonian systems
 /// - Provides a method to calculate the energy of a given state
 ///
@@ -29,3 +33,779 @@ impl Hamiltonian<Vec<i8>> for IsingHamiltonian {
         energy
     }
 }
+
+/// Spin-1/2 Hamiltonian on an arbitrary bond list, in spin operators S = σ/2:
+/// H = Σ_<ij> [J_z S^z_i S^z_j + J_xy/2 (S^+_i S^-_j + S^-_i S^+_j)] - h Σ_i S^z_i - Γ Σ_i S^x_i
+/// - Covers the Ising (J_xy = 0), XXZ and Heisenberg (J_z = J_xy) models
+/// - Bonds usually come from a shared `Lattice`, so exact and Monte Carlo results
+///   refer to the same geometry (including doubled bonds on two-site rings and the
+///   sign-flipped wrap bonds of antiperiodic boundaries)
+#[derive(Clone, Debug)]
+pub struct SpinHamiltonian {
+    pub num_sites: usize,
+    pub bonds: Vec<(usize, usize)>,
+    pub jz: f64,
+    pub jxy: f64,
+    pub field: f64,
+    pub transverse_field: f64,
+    /// Sign (±1) multiplying both couplings of bond b, from `Bond::sign`; empty for all +1.
+    pub bond_signs: Vec<i32>,
+    /// Phase θ_b of bond b, J_xy/2 (e^(iθ_b) S^+_i S^-_j + h.c.): a twist of the
+    /// boundary conditions; empty for none.
+    pub bond_phases: Vec<f64>,
+}
+
+impl SpinHamiltonian {
+    pub fn xxz(lattice: &Lattice, jz: f64, jxy: f64) -> Self {
+        Self {
+            num_sites: lattice.num_sites,
+            bonds: lattice.bonds.iter().map(|b| (b.a, b.b)).collect(),
+            bond_signs: lattice.bonds.iter().map(|b| b.sign).collect(),
+            jz,
+            jxy,
+            field: 0.0,
+            transverse_field: 0.0,
+            bond_phases: Vec::new(),
+        }
+    }
+
+    pub fn heisenberg(lattice: &Lattice, j: f64) -> Self {
+        Self::xxz(lattice, j, j)
+    }
+
+    /// H = -J Σ σ^z σ^z - Γ Σ σ^x in Pauli matrices (the convention of
+    /// `TransverseIsing`), i.e. J_z = -4J and a transverse field 2Γ on S^x.
+    pub fn transverse_ising(lattice: &Lattice, j: f64, gamma: f64) -> Self {
+        Self {
+            transverse_field: 2.0 * gamma,
+            ..Self::xxz(lattice, -4.0 * j, 0.0)
+        }
+    }
+
+    fn bond_sign(&self, bond: usize) -> f64 {
+        self.bond_signs.get(bond).copied().unwrap_or(1) as f64
+    }
+
+    /// Diagonal part of H for the basis state `state` (bit i set = spin i up).
+    pub fn diagonal(&self, state: u32) -> f64 {
+        let sz = |i: usize| if state >> i & 1 == 1 { 0.5 } else { -0.5 };
+        let bonds: f64 = self
+            .bonds
+            .iter()
+            .enumerate()
+            .map(|(b, &(i, j))| self.bond_sign(b) * sz(i) * sz(j))
+            .sum();
+        let total: f64 = (0..self.num_sites).map(sz).sum();
+        self.jz * bonds - self.field * total
+    }
+
+    /// Off-diagonal matrix elements <s|H|state> as (s, amplitude) pairs.
+    fn off_diagonal(&self, state: u32) -> Vec<(u32, Complex)> {
+        let mut out = Vec::new();
+        if self.jxy != 0.0 {
+            for (b, &(i, j)) in self.bonds.iter().enumerate() {
+                if (state >> i ^ state >> j) & 1 == 1 {
+                    // S^+_i S^-_j when j is up, its conjugate when i is up.
+                    let theta = self.bond_phases.get(b).copied().unwrap_or(0.0);
+                    let sign = if state >> j & 1 == 1 { 1.0 } else { -1.0 };
+                    let amplitude =
+                        Complex::expi(sign * theta).scale(0.5 * self.jxy * self.bond_sign(b));
+                    out.push((state ^ (1 << i) ^ (1 << j), amplitude));
+                }
+            }
+        }
+        if self.transverse_field != 0.0 {
+            for i in 0..self.num_sites {
+                let amplitude = Complex::new(-0.5 * self.transverse_field, 0.0);
+                out.push((state ^ (1 << i), amplitude));
+            }
+        }
+        out
+    }
+
+    /// True if the signed bond list maps onto itself under the translation i → i + 1 mod N.
+    fn is_translation_invariant(&self) -> bool {
+        let n = self.num_sites;
+        let key = |(a, b): (usize, usize), sign: i32| (a.min(b), a.max(b), sign);
+        let signs = (0..self.bonds.len()).map(|b| self.bond_signs.get(b).copied().unwrap_or(1));
+        let mut bonds: Vec<_> = self
+            .bonds
+            .iter()
+            .zip(signs.clone())
+            .map(|(&b, s)| key(b, s))
+            .collect();
+        let mut shifted: Vec<_> = self
+            .bonds
+            .iter()
+            .zip(signs)
+            .map(|(&(a, b), s)| key(((a + 1) % n, (b + 1) % n), s))
+            .collect();
+        bonds.sort_unstable();
+        shifted.sort_unstable();
+        bonds == shifted
+    }
+
+    /// Sparse matrix of H in `basis`; needs a field-free transverse term when the
+    /// basis fixes S^z, and translation invariance when it fixes the momentum.
+    pub fn operator(&self, basis: &SpinBasis) -> SparseOperator {
+        assert_eq!(
+            self.num_sites, basis.num_sites,
+            "Basis and Hamiltonian sizes differ"
+        );
+        assert!(
+            basis.num_up.is_none() || self.transverse_field == 0.0,
+            "A transverse field does not conserve S^z"
+        );
+        assert!(
+            basis.momentum.is_none() || self.is_translation_invariant(),
+            "Momentum sectors need a translation-invariant chain"
+        );
+        basis.build(|state| {
+            let mut elements = self.off_diagonal(state);
+            elements.push((state, Complex::new(self.diagonal(state), 0.0)));
+            elements
+        })
+    }
+}
+
+/// Basis of a spin-1/2 chain, optionally restricted to a symmetry sector
+/// - States are bit masks with bit i set for spin i up (at most 30 sites)
+/// - `num_up` fixes S^z_total = num_up - N/2
+/// - `momentum` m fixes the eigenvalue e^(ik) of the translation i → i + 1 mod N,
+///   k = 2πm / N; `states` then holds the smallest member of every admissible orbit
+///   and the basis vectors are the normalised Bloch sums over each orbit
+pub struct SpinBasis {
+    pub num_sites: usize,
+    pub num_up: Option<usize>,
+    pub momentum: Option<usize>,
+    pub states: Vec<u32>,
+    /// Orbit length of each representative (1 for every state without momentum).
+    periods: Vec<usize>,
+}
+
+impl SpinBasis {
+    pub fn new(num_sites: usize, num_up: Option<usize>, momentum: Option<usize>) -> Self {
+        assert!((1..=30).contains(&num_sites), "Between 1 and 30 sites");
+        if let Some(up) = num_up {
+            assert!(up <= num_sites, "More up spins than sites");
+        }
+        if let Some(m) = momentum {
+            assert!(m < num_sites, "Momentum index must be below N");
+        }
+        let mut basis = Self {
+            num_sites,
+            num_up,
+            momentum,
+            states: Vec::new(),
+            periods: Vec::new(),
+        };
+        for state in 0..1u32 << num_sites {
+            if num_up.is_some_and(|up| state.count_ones() as usize != up) {
+                continue;
+            }
+            match momentum {
+                None => {
+                    basis.states.push(state);
+                    basis.periods.push(1);
+                }
+                Some(m) => {
+                    let (representative, _, period) = basis.orbit(state);
+                    if representative == state && (m * period) % num_sites == 0 {
+                        basis.states.push(state);
+                        basis.periods.push(period);
+                    }
+                }
+            }
+        }
+        basis
+    }
+
+    /// The full 2^N-dimensional space.
+    pub fn full(num_sites: usize) -> Self {
+        Self::new(num_sites, None, None)
+    }
+
+    pub fn dim(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Translation i → i + 1 mod N of a basis state.
+    pub fn translate(&self, state: u32) -> u32 {
+        let n = self.num_sites;
+        let mask = (1u32 << n) - 1;
+        ((state << 1) | (state >> (n - 1))) & mask
+    }
+
+    /// (smallest state in the orbit, translations l with T^l state = representative,
+    /// orbit length).
+    fn orbit(&self, state: u32) -> (u32, usize, usize) {
+        let (mut representative, mut shift) = (state, 0);
+        let mut current = state;
+        for l in 1..=self.num_sites {
+            current = self.translate(current);
+            if current == state {
+                return (representative, shift, l);
+            }
+            if current < representative {
+                representative = current;
+                shift = l;
+            }
+        }
+        unreachable!("Translation orbit longer than the chain")
+    }
+
+    /// Index of the basis vector containing `state` with its Bloch phase factor,
+    /// or None if the state lies outside the sector.
+    fn locate(&self, state: u32) -> Option<(usize, usize)> {
+        let (representative, shift) = match self.momentum {
+            None => (state, 0),
+            Some(_) => {
+                let (r, l, _) = self.orbit(state);
+                (r, l)
+            }
+        };
+        self.states
+            .binary_search(&representative)
+            .ok()
+            .map(|index| (index, shift))
+    }
+
+    /// Builds a Hermitian operator from its action on single basis states:
+    /// `action(state)` lists (s, <s|A|state>). In a momentum sector the elements of the
+    /// Bloch states follow as <ψ_r'|A|ψ_r> = Σ_s a_s e^(-ikl) sqrt(R_r / R_r') with
+    /// T^l s = r'; A must commute with the translation.
+    pub fn build<F>(&self, action: F) -> SparseOperator
+    where
+        F: Fn(u32) -> Vec<(u32, Complex)>,
+    {
+        let k = self.momentum.map_or(0.0, |m| {
+            2.0 * std::f64::consts::PI * m as f64 / self.num_sites as f64
+        });
+        let mut row_offsets = vec![0];
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        let mut row: Vec<(usize, Complex)> = Vec::new();
+        for (r, &state) in self.states.iter().enumerate() {
+            row.clear();
+            for (s, amplitude) in action(state) {
+                if let Some((index, l)) = self.locate(s) {
+                    let norm = (self.periods[r] as f64 / self.periods[index] as f64).sqrt();
+                    // Column r of A gives row r by Hermiticity, hence the conjugate.
+                    let value = (Complex::expi(-k * l as f64) * amplitude)
+                        .scale(norm)
+                        .conj();
+                    row.push((index, value));
+                }
+            }
+            row.sort_by_key(|&(c, _)| c);
+            for &(c, v) in row.iter() {
+                if columns.len() > *row_offsets.last().unwrap() && *columns.last().unwrap() == c {
+                    let last = values.len() - 1;
+                    values[last] = values[last] + v;
+                } else {
+                    columns.push(c);
+                    values.push(v);
+                }
+            }
+            row_offsets.push(columns.len());
+        }
+        SparseOperator {
+            dim: self.dim(),
+            row_offsets,
+            columns,
+            values,
+        }
+    }
+
+    /// Operator diagonal in the S^z basis with value `f(state)`; in a momentum
+    /// sector `f` must be translation invariant.
+    pub fn diagonal_operator<F: Fn(u32) -> f64>(&self, f: F) -> SparseOperator {
+        self.build(|state| vec![(state, Complex::new(f(state), 0.0))])
+    }
+}
+
+/// Hermitian operator in compressed sparse row form.
+pub struct SparseOperator {
+    pub dim: usize,
+    pub row_offsets: Vec<usize>,
+    pub columns: Vec<usize>,
+    pub values: Vec<Complex>,
+}
+
+/// Eigenvalue with its normalised eigenvector.
+pub struct Eigenpair {
+    pub value: f64,
+    pub vector: Vec<Complex>,
+}
+
+impl SparseOperator {
+    /// y = A x.
+    pub fn apply(&self, x: &[Complex], y: &mut [Complex]) {
+        for (r, out) in y.iter_mut().enumerate() {
+            let mut sum = Complex::default();
+            for idx in self.row_offsets[r]..self.row_offsets[r + 1] {
+                sum = sum + self.values[idx] * x[self.columns[idx]];
+            }
+            *out = sum;
+        }
+    }
+
+    /// <ψ|A|ψ> for a normalised ψ.
+    pub fn expectation(&self, psi: &[Complex]) -> f64 {
+        let mut y = vec![Complex::default(); self.dim];
+        self.apply(psi, &mut y);
+        inner(psi, &y).re
+    }
+
+    pub fn is_real(&self) -> bool {
+        self.values.iter().all(|v| v.im.abs() < 1e-12)
+    }
+
+    /// Complete spectrum by Householder reduction and QL iteration, ascending;
+    /// for real operators (no momentum, or k = 0, π) of modest dimension.
+    pub fn full_spectrum(&self) -> Vec<Eigenpair> {
+        assert!(
+            self.is_real(),
+            "Dense diagonalisation needs a real operator"
+        );
+        let n = self.dim;
+        let mut a = vec![vec![0.0; n]; n];
+        for (r, row) in a.iter_mut().enumerate() {
+            for idx in self.row_offsets[r]..self.row_offsets[r + 1] {
+                row[self.columns[idx]] += self.values[idx].re;
+            }
+        }
+        let d = symmetric_eigen(&mut a);
+        let mut pairs: Vec<Eigenpair> = (0..n)
+            .map(|k| Eigenpair {
+                value: d[k],
+                vector: (0..n).map(|i| Complex::new(a[i][k], 0.0)).collect(),
+            })
+            .collect();
+        pairs.sort_by(|x, y| x.value.total_cmp(&y.value));
+        pairs
+    }
+
+    /// All eigenvalues, ascending. A complex Hermitian A + iB is diagonalised through
+    /// the real symmetric [[A, -B], [B, A]], whose spectrum is that of A + iB twice.
+    pub fn eigenvalues(&self) -> Vec<f64> {
+        if self.is_real() {
+            return self.full_spectrum().iter().map(|p| p.value).collect();
+        }
+        let n = self.dim;
+        let mut a = vec![vec![0.0; 2 * n]; 2 * n];
+        for r in 0..n {
+            for idx in self.row_offsets[r]..self.row_offsets[r + 1] {
+                let (c, v) = (self.columns[idx], self.values[idx]);
+                a[r][c] += v.re;
+                a[r + n][c + n] += v.re;
+                a[r][c + n] -= v.im;
+                a[r + n][c] += v.im;
+            }
+        }
+        let mut values = symmetric_eigen(&mut a);
+        values.sort_by(f64::total_cmp);
+        values.into_iter().step_by(2).collect()
+    }
+}
+
+/// Eigenvalues (unordered) of the dense real symmetric matrix `a`, which is
+/// overwritten with the eigenvectors in its columns.
+fn symmetric_eigen(a: &mut [Vec<f64>]) -> Vec<f64> {
+    let n = a.len();
+    let (mut d, e) = householder(a);
+    let mut off: Vec<f64> = (0..n)
+        .map(|i| if i + 1 < n { e[i + 1] } else { 0.0 })
+        .collect();
+    tridiagonal_ql(&mut d, &mut off, a);
+    d
+}
+
+/// Lanczos eigensolver for the lowest eigenpairs of a `SparseOperator`
+/// - Two passes without stored Krylov vectors: the first builds the tridiagonal
+///   matrix until the lowest Ritz value changes by less than `tolerance`, the
+///   second regenerates the vectors to assemble the Ritz vector
+/// - Excited levels come from deflation: every later run is projected orthogonal
+///   to the eigenvectors already found, so degenerate levels are returned repeatedly
+/// - Memory is a handful of vectors, which makes ~20-site sectors practical
+pub struct Lanczos {
+    pub max_iterations: usize,
+    pub tolerance: f64,
+}
+
+impl Default for Lanczos {
+    fn default() -> Self {
+        Self {
+            max_iterations: 500,
+            tolerance: 1e-12,
+        }
+    }
+}
+
+impl Lanczos {
+    pub fn ground_state(&self, op: &SparseOperator) -> Eigenpair {
+        self.lowest(op, &[])
+    }
+
+    /// The `count` lowest eigenpairs, ascending.
+    pub fn low_spectrum(&self, op: &SparseOperator, count: usize) -> Vec<Eigenpair> {
+        assert!(count <= op.dim, "Sector has only {} states", op.dim);
+        let mut found: Vec<Eigenpair> = Vec::with_capacity(count);
+        for _ in 0..count {
+            let deflate: Vec<&[Complex]> = found.iter().map(|p| p.vector.as_slice()).collect();
+            let pair = self.lowest(op, &deflate);
+            found.push(pair);
+        }
+        found
+    }
+
+    fn lowest(&self, op: &SparseOperator, deflate: &[&[Complex]]) -> Eigenpair {
+        let mut rng = rand::rng();
+        let mut start: Vec<Complex> = (0..op.dim)
+            .map(|_| Complex::new(rng.random::<f64>() - 0.5, rng.random::<f64>() - 0.5))
+            .collect();
+        project_out(&mut start, deflate);
+        let (alphas, betas) = self.recurrence(op, &start, deflate, None);
+        let (values, vectors) = tridiagonal_eigen(&alphas, &betas);
+        let coefficients: Vec<f64> = vectors.iter().map(|row| row[0]).collect();
+        let mut vector = vec![Complex::default(); op.dim];
+        self.recurrence(op, &start, deflate, Some((&coefficients, &mut vector)));
+        project_out(&mut vector, deflate);
+        let norm = inner(&vector, &vector).re.sqrt();
+        vector.iter_mut().for_each(|x| *x = x.scale(1.0 / norm));
+        Eigenpair {
+            value: values[0],
+            vector,
+        }
+    }
+
+    /// Runs the Lanczos recurrence from `start`. Without `accumulate` it stops at
+    /// convergence and returns the tridiagonal matrix; with it, it repeats exactly
+    /// `coefficients.len()` steps and adds Σ_j c_j v_j into the output vector.
+    fn recurrence(
+        &self,
+        op: &SparseOperator,
+        start: &[Complex],
+        deflate: &[&[Complex]],
+        mut accumulate: Option<(&[f64], &mut Vec<Complex>)>,
+    ) -> (Vec<f64>, Vec<f64>) {
+        let steps = accumulate
+            .as_ref()
+            .map_or(self.max_iterations.min(op.dim + 1), |(c, _)| c.len());
+        let norm = inner(start, start).re.sqrt();
+        let mut v: Vec<Complex> = start.iter().map(|x| x.scale(1.0 / norm)).collect();
+        let mut v_prev = vec![Complex::default(); op.dim];
+        let mut w = vec![Complex::default(); op.dim];
+        let (mut alphas, mut betas) = (Vec::new(), Vec::new());
+        let mut beta = 0.0;
+        let mut previous = f64::INFINITY;
+        for j in 0..steps {
+            if let Some((c, out)) = accumulate.as_mut() {
+                for (o, x) in out.iter_mut().zip(&v) {
+                    *o = *o + x.scale(c[j]);
+                }
+            }
+            op.apply(&v, &mut w);
+            project_out(&mut w, deflate);
+            let alpha = inner(&v, &w).re;
+            for ((wi, vi), pi) in w.iter_mut().zip(&v).zip(&v_prev) {
+                *wi = *wi - vi.scale(alpha) - pi.scale(beta);
+            }
+            alphas.push(alpha);
+            beta = inner(&w, &w).re.sqrt();
+            if accumulate.is_none() {
+                if beta < 1e-10 {
+                    break;
+                }
+                if j % 10 == 9 {
+                    let lowest = tridiagonal_eigen(&alphas, &betas).0[0];
+                    if (lowest - previous).abs() < self.tolerance * lowest.abs().max(1.0) {
+                        break;
+                    }
+                    previous = lowest;
+                }
+            }
+            if j + 1 == steps {
+                break;
+            }
+            betas.push(beta);
+            std::mem::swap(&mut v_prev, &mut v);
+            for (vi, wi) in v.iter_mut().zip(&w) {
+                *vi = wi.scale(1.0 / beta);
+            }
+        }
+        betas.truncate(alphas.len().saturating_sub(1));
+        (alphas, betas)
+    }
+}
+
+/// Σ_i conj(a_i) b_i.
+fn inner(a: &[Complex], b: &[Complex]) -> Complex {
+    a.iter()
+        .zip(b)
+        .fold(Complex::default(), |sum, (x, y)| sum + x.conj() * *y)
+}
+
+/// Removes the components along the (orthonormal) `vectors`.
+fn project_out(x: &mut [Complex], vectors: &[&[Complex]]) {
+    for u in vectors {
+        let overlap = inner(u, x);
+        for (xi, ui) in x.iter_mut().zip(u.iter()) {
+            *xi = *xi - *ui * overlap;
+        }
+    }
+}
+
+/// Eigenvalues (ascending) and eigenvectors (`vectors[i][k]` = component i of
+/// eigenvector k) of the symmetric tridiagonal matrix with diagonal `diagonal`
+/// and off-diagonal `off`.
+fn tridiagonal_eigen(diagonal: &[f64], off: &[f64]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = diagonal.len();
+    let mut d = diagonal.to_vec();
+    let mut e: Vec<f64> = (0..n).map(|i| off.get(i).copied().unwrap_or(0.0)).collect();
+    let mut z: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|k| if i == k { 1.0 } else { 0.0 }).collect())
+        .collect();
+    tridiagonal_ql(&mut d, &mut e, &mut z);
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| d[a].total_cmp(&d[b]));
+    let values = order.iter().map(|&k| d[k]).collect();
+    let vectors = z
+        .iter()
+        .map(|row| order.iter().map(|&k| row[k]).collect())
+        .collect();
+    (values, vectors)
+}
+
+/// Implicit QL iteration on a symmetric tridiagonal matrix (diagonal `d`, `e[i]`
+/// coupling i and i + 1, `e[n - 1]` = 0). `z` holds the accumulated transformation
+/// on entry and the eigenvectors in its columns on exit; `d` ends as the eigenvalues.
+fn tridiagonal_ql(d: &mut [f64], e: &mut [f64], z: &mut [Vec<f64>]) {
+    let n = d.len();
+    for l in 0..n {
+        let mut iterations = 0;
+        loop {
+            let mut m = l;
+            while m + 1 < n {
+                let dd = d[m].abs() + d[m + 1].abs();
+                if e[m].abs() <= f64::EPSILON * dd {
+                    break;
+                }
+                m += 1;
+            }
+            if m == l {
+                break;
+            }
+            iterations += 1;
+            assert!(iterations < 100, "QL iteration did not converge");
+            let mut g = (d[l + 1] - d[l]) / (2.0 * e[l]);
+            let mut r = g.hypot(1.0);
+            g = d[m] - d[l] + e[l] / (g + r.copysign(g));
+            let (mut s, mut c, mut p) = (1.0, 1.0, 0.0);
+            let mut underflow = false;
+            for i in (l..m).rev() {
+                let f = s * e[i];
+                let b = c * e[i];
+                r = f.hypot(g);
+                e[i + 1] = r;
+                if r == 0.0 {
+                    d[i + 1] -= p;
+                    e[m] = 0.0;
+                    underflow = true;
+                    break;
+                }
+                s = f / r;
+                c = g / r;
+                g = d[i + 1] - p;
+                r = (d[i] - g) * s + 2.0 * c * b;
+                p = s * r;
+                d[i + 1] = g + p;
+                g = c * r - b;
+                for row in z.iter_mut() {
+                    let f = row[i + 1];
+                    row[i + 1] = s * row[i] + c * f;
+                    row[i] = c * row[i] - s * f;
+                }
+            }
+            if underflow {
+                continue;
+            }
+            d[l] -= p;
+            e[l] = g;
+            e[m] = 0.0;
+        }
+    }
+}
+
+/// Householder reduction of the real symmetric matrix `a` to tridiagonal form;
+/// returns the diagonal and the sub-diagonal (`e[i]` couples i - 1 and i) and
+/// leaves the orthogonal transformation in `a`.
+fn householder(a: &mut [Vec<f64>]) -> (Vec<f64>, Vec<f64>) {
+    let n = a.len();
+    let mut d = vec![0.0; n];
+    let mut e = vec![0.0; n];
+    for i in (1..n).rev() {
+        let l = i - 1;
+        let mut h = 0.0;
+        if l > 0 {
+            let scale: f64 = (0..=l).map(|k| a[i][k].abs()).sum();
+            if scale == 0.0 {
+                e[i] = a[i][l];
+            } else {
+                for x in a[i][..=l].iter_mut() {
+                    *x /= scale;
+                    h += *x * *x;
+                }
+                let f = a[i][l];
+                let g = if f >= 0.0 { -h.sqrt() } else { h.sqrt() };
+                e[i] = scale * g;
+                h -= f * g;
+                a[i][l] = f - g;
+                let mut f = 0.0;
+                for j in 0..=l {
+                    a[j][i] = a[i][j] / h;
+                    let g = (0..=j).map(|k| a[j][k] * a[i][k]).sum::<f64>()
+                        + (j + 1..=l).map(|k| a[k][j] * a[i][k]).sum::<f64>();
+                    e[j] = g / h;
+                    f += e[j] * a[i][j];
+                }
+                let hh = f / (h + h);
+                for j in 0..=l {
+                    let f = a[i][j];
+                    let g = e[j] - hh * f;
+                    e[j] = g;
+                    for k in 0..=j {
+                        a[j][k] -= f * e[k] + g * a[i][k];
+                    }
+                }
+            }
+        } else {
+            e[i] = a[i][l];
+        }
+        d[i] = h;
+    }
+    d[0] = 0.0;
+    e[0] = 0.0;
+    for i in 0..n {
+        if d[i] != 0.0 {
+            for j in 0..i {
+                let g: f64 = (0..i).map(|k| a[i][k] * a[k][j]).sum();
+                for row in a.iter_mut().take(i) {
+                    row[j] -= g * row[i];
+                }
+            }
+        }
+        d[i] = a[i][i];
+        a[i][i] = 1.0;
+        for row in a.iter_mut().take(i) {
+            row[i] = 0.0;
+        }
+        a[i][..i].fill(0.0);
+    }
+    (d, e)
+}
+
+/// Ground-state energy of the periodic transverse-field Ising chain (Pauli convention,
+/// even fermion-parity sector): E_0 = -Σ_n sqrt(J² + Γ² - 2JΓ cos k_n), k_n = (2n + 1)π / N.
+pub fn transverse_ising_chain_ground_energy(length: usize, j: f64, gamma: f64) -> f64 {
+    -(0..length)
+        .map(|n| {
+            let k = (2 * n + 1) as f64 * std::f64::consts::PI / length as f64;
+            (j * j + gamma * gamma - 2.0 * j * gamma * k.cos()).sqrt()
+        })
+        .sum::<f64>()
+}
+
+pub fn exact_diagonalization_example() {
+    let lanczos = Lanczos::default();
+    // Heisenberg ring: e_0 → 1/4 - ln 2 ≈ -0.4431 per site for N → ∞.
+    for n in [12, 16, 20] {
+        let chain = Lattice::hypercubic(1, n, Boundary::Periodic);
+        let basis = SpinBasis::new(n, Some(n / 2), Some(0));
+        let ground =
+            lanczos.ground_state(&SpinHamiltonian::heisenberg(&chain, 1.0).operator(&basis));
+        println!(
+            "Heisenberg N = {:2} (dim {:5}): e_0 = {:.6}",
+            n,
+            basis.dim(),
+            ground.value / n as f64
+        );
+    }
+    let chain = Lattice::hypercubic(1, 12, Boundary::Periodic);
+    let h = SpinHamiltonian::transverse_ising(&chain, 1.0, 1.0).operator(&SpinBasis::full(12));
+    let levels = lanczos.low_spectrum(&h, 3);
+    println!(
+        "Critical Ising N = 12: E = {:.6}, {:.6}, {:.6} (free fermions: E_0 = {:.6})",
+        levels[0].value,
+        levels[1].value,
+        levels[2].value,
+        transverse_ising_chain_ground_energy(12, 1.0, 1.0)
+    );
+}
+
+#[test]
+fn test_sectors_reproduce_full_spectrum() {
+    // Every level of the XXZ ring appears in exactly one (S^z, k) sector.
+    let n = 6;
+    let chain = Lattice::hypercubic(1, n, Boundary::Periodic);
+    let h = SpinHamiltonian {
+        field: 0.3,
+        ..SpinHamiltonian::xxz(&chain, 1.0, 0.7)
+    };
+    let full: Vec<f64> = h
+        .operator(&SpinBasis::full(n))
+        .full_spectrum()
+        .iter()
+        .map(|p| p.value)
+        .collect();
+    let mut sectors = Vec::new();
+    let lanczos = Lanczos::default();
+    for up in 0..=n {
+        for m in 0..n {
+            let basis = SpinBasis::new(n, Some(up), Some(m));
+            if basis.dim() > 0 {
+                let op = h.operator(&basis);
+                sectors.extend(
+                    lanczos
+                        .low_spectrum(&op, basis.dim())
+                        .iter()
+                        .map(|p| p.value),
+                );
+            }
+        }
+    }
+    sectors.sort_by(f64::total_cmp);
+    assert_eq!(sectors.len(), 1 << n);
+    for (a, b) in full.iter().zip(&sectors) {
+        assert!((a - b).abs() < 1e-8, "{} vs {}", a, b);
+    }
+}
+
+#[test]
+fn test_lanczos_ground_states() {
+    let lanczos = Lanczos::default();
+    // Four-site Heisenberg ring: E_0 = -2J.
+    let ring = Lattice::hypercubic(1, 4, Boundary::Periodic);
+    let ground = lanczos.ground_state(
+        &SpinHamiltonian::heisenberg(&ring, 1.0).operator(&SpinBasis::new(4, Some(2), None)),
+    );
+    assert!((ground.value + 2.0).abs() < 1e-10);
+
+    // Classical antiperiodic ring: the sign-flipped wrap bond forces one broken bond.
+    let twisted = Lattice::hypercubic(1, 6, Boundary::Antiperiodic);
+    let op = SpinHamiltonian::transverse_ising(&twisted, 1.0, 0.0).operator(&SpinBasis::full(6));
+    assert!((lanczos.ground_state(&op).value + 4.0).abs() < 1e-10);
+
+    // Transverse-field Ising ring against free fermions, with the residual of the vector.
+    let chain = Lattice::hypercubic(1, 10, Boundary::Periodic);
+    let op = SpinHamiltonian::transverse_ising(&chain, 1.0, 0.7).operator(&SpinBasis::full(10));
+    let ground = lanczos.ground_state(&op);
+    let exact = transverse_ising_chain_ground_energy(10, 1.0, 0.7);
+    assert!(
+        (ground.value - exact).abs() < 1e-9,
+        "{} vs {}",
+        ground.value,
+        exact
+    );
+    assert!((op.expectation(&ground.vector) - exact).abs() < 1e-8);
+}
@@ -1,58 +1,801 @@
-use rand::Rng;
+use crate::algorithms::parallel_tempering::Replica;
+use crate::diagnostics::{jackknife, jackknife_blocks, mean_with_error, Estimate};
+use crate::models::lattice::{Bond, Boundary, Lattice, Neighbor, SecondNeighbors};
+use crate::physics::correlations::Correlations;
+use crate::physics::hilbert::IsingHamiltonian;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 /// Ising model for simulating magnetic spin systems
 /// - Spins interact with neighbors and are influenced by temperature
-/// - Each step flips spins based on energy change and Metropolis criteria
+/// - Each step flips spins based on energy change and the configured update rule
+/// - Geometry and boundary conditions come from the shared `Lattice`
+/// - Couplings default to J = 1 without field; see `IsingCouplings` and `set_couplings`
 pub struct IsingModel {
     pub size: usize,
     pub temperature: f64,
-    pub spins: Vec<Vec<i32>>,
+    pub lattice: Lattice,
+    pub spins: Vec<i32>,
+    pub update_rule: UpdateRule,
+    pub site_order: SiteOrder,
+    couplings: IsingCouplings,
+    second_bonds: Vec<Bond>,
+    second_neighbors: Vec<Vec<Neighbor>>,
+    table: Option<BoltzmannTable>,
+    /// Running mean of the Wolff cluster size, which sets the moves per sweep.
+    mean_cluster_size: f64,
+}
+
+/// Acceptance rule for single-spin flips in `IsingModel::step`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpdateRule {
+    /// min(1, exp(-ΔE/T))
+    Metropolis,
+    /// Glauber heat bath, 1 / (1 + exp(ΔE/T))
+    Glauber,
+}
+
+/// Order in which `IsingModel::step` visits the N sites of a sweep
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SiteOrder {
+    /// N sites drawn uniformly at random (with repetition)
+    Random,
+    /// Typewriter order 0, 1, ..., N - 1
+    Sequential,
+    /// All sites of sublattice 0, then all of sublattice 1
+    Checkerboard,
+}
+
+/// Couplings of H = -Σ_<ij> J_d s_i s_j - J_2 Σ_<<ij>> s_i s_j - Σ_i (h + (-1)^i h_s) s_i
+/// - `j[d]` couples nearest neighbours along bond direction d (J_x, J_y, ...)
+/// - `j2` couples the `second_neighbors` pairs: diagonal for the frustrated J1–J2
+///   model, axial for the ANNNI model
+/// - (-1)^i is +1 on checkerboard sublattice 0 and -1 on sublattice 1
+#[derive(Clone, Debug, PartialEq)]
+pub struct IsingCouplings {
+    pub j: Vec<f64>,
+    pub j2: f64,
+    pub second_neighbors: SecondNeighbors,
+    pub field: f64,
+    pub staggered_field: f64,
+}
+
+impl IsingCouplings {
+    /// J = 1 on every bond direction, no second neighbours and no field.
+    pub fn ferromagnetic(lattice: &Lattice) -> Self {
+        Self {
+            j: vec![1.0; lattice.num_directions()],
+            j2: 0.0,
+            second_neighbors: SecondNeighbors::Diagonal,
+            field: 0.0,
+            staggered_field: 0.0,
+        }
+    }
+
+    /// Isotropic J and uniform h of an `IsingHamiltonian`.
+    pub fn from_hamiltonian(lattice: &Lattice, hamiltonian: &IsingHamiltonian) -> Self {
+        Self {
+            j: vec![hamiltonian.j; lattice.num_directions()],
+            field: hamiltonian.h,
+            ..Self::ferromagnetic(lattice)
+        }
+    }
+
+    /// True without second neighbours and fields, where the Fortuin–Kasteleyn
+    /// cluster updates apply.
+    pub fn is_cluster_compatible(&self) -> bool {
+        self.j2 == 0.0 && self.field == 0.0 && self.staggered_field == 0.0
+    }
+}
+
+/// Acceptance probabilities of a single-spin flip for every local environment
+/// - The key is linear in the sign-weighted neighbour spin sum of each bond direction
+///   and of the second neighbours, plus the sublattice and the spin itself, so it is
+///   accumulated in one pass over the neighbours
+/// - Valid for one temperature and update rule; `IsingModel` rebuilds it when
+///   either changes and drops it when the couplings change
+#[derive(Clone, Debug)]
+struct BoltzmannTable {
+    temperature: f64,
+    rule: UpdateRule,
+    /// Key stride of each channel: the bond directions, then the second neighbours.
+    strides: Vec<usize>,
+    sublattice_stride: usize,
+    spin_stride: usize,
+    /// Key of the environment with every channel sum at its minimum.
+    offset: usize,
+    probabilities: Vec<f64>,
+}
+
+impl BoltzmannTable {
+    fn new(model: &IsingModel) -> Self {
+        let lattice = &model.lattice;
+        let c = &model.couplings;
+        let num_directions = lattice.num_directions();
+        // Largest number of neighbours per site in each channel.
+        let mut max_counts = vec![0; num_directions + 1];
+        for site in 0..lattice.num_sites {
+            let mut counts = vec![0; num_directions + 1];
+            for n in &lattice.neighbors[site] {
+                counts[lattice.bonds[n.bond].direction] += 1;
+            }
+            counts[num_directions] = model.second_neighbors[site].len();
+            for (max, count) in max_counts.iter_mut().zip(counts) {
+                *max = (*max).max(count);
+            }
+        }
+
+        let mut strides = Vec::with_capacity(max_counts.len());
+        let mut size = 1;
+        let mut offset = 0;
+        for &m in &max_counts {
+            strides.push(size);
+            offset += size * m;
+            size *= 2 * m + 1;
+        }
+        let couplings: Vec<f64> = c.j.iter().copied().chain([c.j2]).collect();
+        let mut probabilities = Vec::with_capacity(4 * size);
+        for key in 0..4 * size {
+            let mut rest = key;
+            let mut field = 0.0;
+            for (&m, &j) in max_counts.iter().zip(&couplings) {
+                let sum = (rest % (2 * m + 1)) as f64 - m as f64;
+                rest /= 2 * m + 1;
+                field += j * sum;
+            }
+            let sublattice_sign = if rest % 2 == 0 { 1.0 } else { -1.0 };
+            let spin = if rest / 2 == 1 { 1.0 } else { -1.0 };
+            field += c.field + sublattice_sign * c.staggered_field;
+            probabilities.push(acceptance(
+                model.update_rule,
+                2.0 * spin * field,
+                model.temperature,
+            ));
+        }
+        Self {
+            temperature: model.temperature,
+            rule: model.update_rule,
+            strides,
+            sublattice_stride: size,
+            spin_stride: 2 * size,
+            offset,
+            probabilities,
+        }
+    }
+
+    fn is_current(&self, model: &IsingModel) -> bool {
+        self.temperature == model.temperature && self.rule == model.update_rule
+    }
+}
+
+/// Probability of accepting a move with energy change `delta_e`.
+fn acceptance(rule: UpdateRule, delta_e: f64, temperature: f64) -> f64 {
+    match rule {
+        UpdateRule::Metropolis => {
+            if delta_e <= 0.0 {
+                1.0
+            } else {
+                (-delta_e / temperature).exp()
+            }
+        }
+        UpdateRule::Glauber => {
+            // At T = 0, ΔE = 0 would give 0/0; the T → 0 limit of the rate is 1/2.
+            let x = if delta_e == 0.0 {
+                0.0
+            } else {
+                delta_e / temperature
+            };
+            1.0 / (1.0 + x.exp())
+        }
+    }
+}
+
+/// Fortuin–Kasteleyn bond configuration produced by a Swendsen–Wang step
+/// - `active_bonds[b]` marks whether lattice bond `b` is occupied
+/// - `labels[site]` is the cluster index of a site, `cluster_sizes[c]` its size
+pub struct BondConfiguration {
+    pub active_bonds: Vec<bool>,
+    pub labels: Vec<usize>,
+    pub cluster_sizes: Vec<usize>,
+}
+
+impl BondConfiguration {
+    pub fn num_clusters(&self) -> usize {
+        self.cluster_sizes.len()
+    }
+
+    /// Improved estimator of N<m²> = <Σ_C |C|²> / N from the cluster sizes.
+    /// Dividing by T gives the susceptibility per spin.
+    pub fn improved_susceptibility(&self) -> f64 {
+        let num_spins: usize = self.cluster_sizes.iter().sum();
+        self.cluster_sizes
+            .iter()
+            .map(|&c| (c * c) as f64)
+            .sum::<f64>()
+            / num_spins as f64
+    }
+}
+
+/// Thermodynamic observables measured on an `IsingModel`
+/// - Keeps the per-spin energy and magnetisation time series of the measurement phase
+/// - Every derived quantity carries a jackknife error that accounts for autocorrelation
+/// - Specific heat, susceptibility and Binder cumulant are per spin
+pub struct IsingObservables {
+    pub temperature: f64,
+    pub num_spins: usize,
+    pub energy_series: Vec<f64>,
+    pub magnetization_series: Vec<f64>,
+    pub energy: Estimate,
+    pub magnetization: Estimate,
+    pub abs_magnetization: Estimate,
+    pub magnetization_squared: Estimate,
+    pub magnetization_fourth: Estimate,
+    pub specific_heat: Estimate,
+    pub susceptibility: Estimate,
+    pub binder_cumulant: Estimate,
+}
+
+impl IsingObservables {
+    pub fn from_series(
+        temperature: f64,
+        num_spins: usize,
+        energy_series: Vec<f64>,
+        magnetization_series: Vec<f64>,
+    ) -> Self {
+        let n = num_spins as f64;
+        let e2: Vec<f64> = energy_series.iter().map(|e| e * e).collect();
+        let abs_m: Vec<f64> = magnetization_series.iter().map(|m| m.abs()).collect();
+        let m2: Vec<f64> = magnetization_series.iter().map(|m| m * m).collect();
+        let m4: Vec<f64> = m2.iter().map(|m| m * m).collect();
+        let blocks = jackknife_blocks(&[&energy_series, &abs_m]);
+
+        let specific_heat = jackknife(&[&energy_series, &e2], blocks, |x| {
+            n * (x[1] - x[0] * x[0]) / (temperature * temperature)
+        });
+        let susceptibility = jackknife(&[&abs_m, &m2], blocks, |x| {
+            n * (x[1] - x[0] * x[0]) / temperature
+        });
+        let binder_cumulant = jackknife(&[&m2, &m4], blocks, |x| 1.0 - x[1] / (3.0 * x[0] * x[0]));
+
+        Self {
+            temperature,
+            num_spins,
+            energy: mean_with_error(&energy_series),
+            magnetization: mean_with_error(&magnetization_series),
+            abs_magnetization: mean_with_error(&abs_m),
+            magnetization_squared: mean_with_error(&m2),
+            magnetization_fourth: mean_with_error(&m4),
+            specific_heat,
+            susceptibility,
+            binder_cumulant,
+            energy_series,
+            magnetization_series,
+        }
+    }
 }
 
 impl IsingModel {
+    /// Square L×L lattice with periodic boundaries.
     pub fn new(size: usize, temperature: f64) -> Self {
+        Self::with_lattice(Lattice::square(size, Boundary::Periodic), temperature)
+    }
+
+    pub fn with_lattice(lattice: Lattice, temperature: f64) -> Self {
         let mut rng = rand::rng();
-        let spins = (0..size)
-            .map(|_| {
-                (0..size)
-                    .map(|_| if rng.random_bool(0.5) { 1 } else { -1 })
-                    .collect()
-            })
+        let spins = (0..lattice.num_sites)
+            .map(|_| if rng.random_bool(0.5) { 1 } else { -1 })
             .collect();
         Self {
-            size,
+            size: lattice.dims[0],
             temperature,
+            couplings: IsingCouplings::ferromagnetic(&lattice),
+            second_bonds: Vec::new(),
+            second_neighbors: vec![Vec::new(); lattice.num_sites],
+            table: None,
+            lattice,
             spins,
+            update_rule: UpdateRule::Metropolis,
+            site_order: SiteOrder::Random,
+            mean_cluster_size: 0.0,
+        }
+    }
+
+    pub fn couplings(&self) -> &IsingCouplings {
+        &self.couplings
+    }
+
+    /// Replaces the couplings; `j` needs one entry per lattice bond direction, and
+    /// second neighbours are only wired up when `j2` is nonzero.
+    pub fn set_couplings(&mut self, couplings: IsingCouplings) {
+        assert_eq!(
+            couplings.j.len(),
+            self.lattice.num_directions(),
+            "Need one coupling per bond direction"
+        );
+        self.second_bonds = if couplings.j2 != 0.0 {
+            self.lattice
+                .second_neighbor_bonds(couplings.second_neighbors)
+        } else {
+            Vec::new()
+        };
+        self.second_neighbors = vec![Vec::new(); self.num_spins()];
+        for (index, b) in self.second_bonds.iter().enumerate() {
+            for (from, to) in [(b.a, b.b), (b.b, b.a)] {
+                self.second_neighbors[from].push(Neighbor {
+                    site: to,
+                    bond: index,
+                    sign: b.sign,
+                });
+            }
+        }
+        self.couplings = couplings;
+        self.table = None;
+    }
+
+    /// Builder form of `set_couplings`.
+    pub fn with_couplings(mut self, couplings: IsingCouplings) -> Self {
+        self.set_couplings(couplings);
+        self
+    }
+
+    pub fn num_spins(&self) -> usize {
+        self.lattice.num_sites
+    }
+
+    /// Sum of the (sign-carrying) neighbour spins of `site`.
+    pub fn local_field(&self, site: usize) -> i32 {
+        self.lattice.neighbors[site]
+            .iter()
+            .map(|n| n.sign * self.spins[n.site])
+            .sum()
+    }
+
+    /// Field h_i acting on `site`: Σ_j J_ij s_j over nearest and second neighbours
+    /// plus the uniform and staggered field, so that ΔE = 2 s_i h_i.
+    pub fn effective_field(&self, site: usize) -> f64 {
+        let c = &self.couplings;
+        let mut field = c.field + self.sublattice_sign(site) * c.staggered_field;
+        for n in &self.lattice.neighbors[site] {
+            let j = c.j[self.lattice.bonds[n.bond].direction];
+            field += j * (n.sign * self.spins[n.site]) as f64;
+        }
+        for n in &self.second_neighbors[site] {
+            field += c.j2 * (n.sign * self.spins[n.site]) as f64;
+        }
+        field
+    }
+
+    fn sublattice_sign(&self, site: usize) -> f64 {
+        if self.lattice.sublattice(site) == 0 {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+
+    /// Rebuilds the acceptance table if the temperature, update rule or couplings changed.
+    fn refresh_table(&mut self) {
+        if !self.table.as_ref().is_some_and(|t| t.is_current(self)) {
+            self.table = Some(BoltzmannTable::new(self));
         }
     }
 
+    /// Key of the local environment of `site` in the acceptance table.
+    fn table_key(&self, table: &BoltzmannTable, site: usize) -> usize {
+        let num_directions = table.strides.len() - 1;
+        let mut key = table.offset as isize;
+        for n in &self.lattice.neighbors[site] {
+            let stride = table.strides[self.lattice.bonds[n.bond].direction] as isize;
+            key += stride * (n.sign * self.spins[n.site]) as isize;
+        }
+        for n in &self.second_neighbors[site] {
+            key += table.strides[num_directions] as isize * (n.sign * self.spins[n.site]) as isize;
+        }
+        key as usize
+            + table.sublattice_stride * self.lattice.sublattice(site)
+            + table.spin_stride * (self.spins[site] > 0) as usize
+    }
+
+    /// One sweep of N single-spin updates using `update_rule` and `site_order`.
     pub fn step(&mut self) {
+        self.refresh_table();
         let mut rng = rand::rng();
-        for _ in 0..self.size * self.size {
-            let i = rng.random_range(0..self.size);
-            let j = rng.random_range(0..self.size);
-            let delta_e = 2
-                * self.spins[i][j]
-                * (self.spins[(i + 1) % self.size][j]
-                    + self.spins[(i + self.size - 1) % self.size][j]
-                    + self.spins[i][(j + 1) % self.size]
-                    + self.spins[i][(j + self.size - 1) % self.size]);
-            if delta_e <= 0 || rng.random_bool((-delta_e as f64 / self.temperature).exp()) {
-                self.spins[i][j] = -self.spins[i][j];
+        let n = self.num_spins();
+        match self.site_order {
+            SiteOrder::Random => {
+                for _ in 0..n {
+                    let site = rng.random_range(0..n);
+                    self.update_site(site, &mut rng);
+                }
+            }
+            SiteOrder::Sequential => {
+                for site in 0..n {
+                    self.update_site(site, &mut rng);
+                }
+            }
+            SiteOrder::Checkerboard => {
+                let parity: Vec<usize> = (0..n).map(|s| self.lattice.sublattice(s)).collect();
+                for sublattice in 0..2 {
+                    for site in (0..n).filter(|&s| parity[s] == sublattice) {
+                        self.update_site(site, &mut rng);
+                    }
+                }
             }
         }
     }
 
+    fn update_site<R: Rng>(&mut self, site: usize, rng: &mut R) {
+        if self.should_flip(site, rng) {
+            self.spins[site] = -self.spins[site];
+        }
+    }
+
+    /// Energy change of flipping the spin on `site`.
+    pub fn delta_energy(&self, site: usize) -> f64 {
+        2.0 * self.spins[site] as f64 * self.effective_field(site)
+    }
+
+    /// Looks the acceptance probability up in the table, which the caller must
+    /// have refreshed for the current parameters.
+    fn should_flip<R: Rng>(&self, site: usize, rng: &mut R) -> bool {
+        let table = self.table.as_ref().expect("Acceptance table not built");
+        let p = table.probabilities[self.table_key(table, site)];
+        p >= 1.0 || rng.random::<f64>() < p
+    }
+
+    /// Multithreaded checkerboard sweep
+    /// - Sites of one sublattice have no bonds between them, so each half is split
+    ///   into `num_threads` chunks that are updated concurrently from the same
+    ///   frozen configuration of the other half
+    /// - Every thread draws from its own `SmallRng` stream seeded from the thread-local RNG
+    /// - Samples the same ensemble as `step` with `SiteOrder::Checkerboard`;
+    ///   requires a bipartite lattice without second-neighbour couplings
+    pub fn parallel_step(&mut self, num_threads: usize) {
+        assert!(num_threads >= 1, "Need at least one thread");
+        assert!(
+            self.lattice.is_bipartite() && self.second_bonds.is_empty(),
+            "Checkerboard updates need a bipartite lattice"
+        );
+        self.refresh_table();
+        let mut seeder = rand::rng();
+        let mut rngs: Vec<SmallRng> = (0..num_threads)
+            .map(|_| SmallRng::from_rng(&mut seeder))
+            .collect();
+        for sublattice in 0..2 {
+            let sites: Vec<usize> = (0..self.num_spins())
+                .filter(|&s| self.lattice.sublattice(s) == sublattice)
+                .collect();
+            let chunk_size = sites.len().div_ceil(num_threads).max(1);
+            let model = &*self;
+            let flips: Vec<Vec<usize>> = std::thread::scope(|scope| {
+                let handles: Vec<_> = sites
+                    .chunks(chunk_size)
+                    .zip(rngs.iter_mut())
+                    .map(|(chunk, rng)| {
+                        scope.spawn(move || {
+                            chunk
+                                .iter()
+                                .copied()
+                                .filter(|&site| model.should_flip(site, rng))
+                                .collect()
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|h| h.join().expect("Sweep thread panicked"))
+                    .collect()
+            });
+            for site in flips.into_iter().flatten() {
+                self.spins[site] = -self.spins[site];
+            }
+        }
+    }
+
+    /// Probability 1 - exp(-2|J_d|/T) of activating a satisfied bond, per direction.
+    fn bond_probabilities(&self) -> Vec<f64> {
+        assert!(
+            self.couplings.is_cluster_compatible(),
+            "Cluster updates need nearest-neighbour couplings without field"
+        );
+        self.couplings
+            .j
+            .iter()
+            .map(|j| 1.0 - (-2.0 * j.abs() / self.temperature).exp())
+            .collect()
+    }
+
+    /// True if the bond to neighbour `n` of a site with spin `s` is satisfied.
+    fn satisfied(&self, n: &Neighbor, s: i32) -> bool {
+        let j = self.couplings.j[self.lattice.bonds[n.bond].direction];
+        j * (n.sign * self.spins[n.site] * s) as f64 > 0.0
+    }
+
+    /// Wolff single-cluster update
+    /// - Grows a cluster from a random seed, adding neighbours across satisfied
+    ///   bonds with probability 1 - exp(-2|J|/T), then flips the whole cluster
+    /// - Returns the cluster size so callers can convert moves to sweeps
+    pub fn wolff_step(&mut self) -> usize {
+        let mut rng = rand::rng();
+        let p_add = self.bond_probabilities();
+        let seed = rng.random_range(0..self.num_spins());
+        let mut in_cluster = vec![false; self.num_spins()];
+        in_cluster[seed] = true;
+        let mut stack = vec![seed];
+        let mut cluster_size = 1;
+        while let Some(site) = stack.pop() {
+            let s = self.spins[site];
+            for n in &self.lattice.neighbors[site] {
+                if !in_cluster[n.site]
+                    && self.satisfied(n, s)
+                    && rng.random::<f64>() < p_add[self.lattice.bonds[n.bond].direction]
+                {
+                    in_cluster[n.site] = true;
+                    stack.push(n.site);
+                    cluster_size += 1;
+                }
+            }
+        }
+        for (spin, &flip) in self.spins.iter_mut().zip(&in_cluster) {
+            if flip {
+                *spin = -*spin;
+            }
+        }
+        cluster_size
+    }
+
+    /// About N / <cluster size> Wolff moves (one sweep-equivalent), returning the
+    /// number of moves; see `cluster_sweep`.
+    pub fn wolff_sweep(&mut self) -> usize {
+        let mut mean = self.mean_cluster_size;
+        let moves = cluster_sweep(self.num_spins(), &mut mean, || self.wolff_step());
+        self.mean_cluster_size = mean;
+        moves
+    }
+
+    /// Resets the configuration to a random arrangement with exactly
+    /// round(N (1 + m) / 2) up spins, e.g. a binary alloy of fixed composition.
+    pub fn randomize_with_magnetization(&mut self, magnetization: f64) {
+        let n = self.num_spins();
+        let up = ((n as f64 * (1.0 + magnetization) / 2.0).round() as usize).min(n);
+        for (i, spin) in self.spins.iter_mut().enumerate() {
+            *spin = if i < up { 1 } else { -1 };
+        }
+        self.spins.shuffle(&mut rand::rng());
+    }
+
+    /// Kawasaki spin-exchange dynamics
+    /// - N attempts to swap a random site with a random nearest neighbour of opposite spin
+    /// - Metropolis acceptance on the energy change, so the magnetisation is conserved
+    /// - Returns the number of accepted exchanges
+    pub fn kawasaki_sweep(&mut self) -> usize {
+        let mut rng = rand::rng();
+        let mut accepted = 0;
+        for _ in 0..self.num_spins() {
+            let a = rng.random_range(0..self.num_spins());
+            let neighbors = &self.lattice.neighbors[a];
+            if neighbors.is_empty() {
+                continue;
+            }
+            let b = neighbors[rng.random_range(0..neighbors.len())].site;
+            let (sa, sb) = (self.spins[a], self.spins[b]);
+            if sa == sb {
+                continue;
+            }
+            // Exchanging opposite spins flips both; the a–b bonds themselves are unchanged.
+            let shared: f64 = neighbors
+                .iter()
+                .filter(|n| n.site == b)
+                .map(|n| self.couplings.j[self.lattice.bonds[n.bond].direction] * n.sign as f64)
+                .sum();
+            let delta_e =
+                self.delta_energy(a) + self.delta_energy(b) - 4.0 * shared * (sa * sb) as f64;
+            if delta_e <= 0.0 || rng.random_bool((-delta_e / self.temperature).exp()) {
+                self.spins[a] = sb;
+                self.spins[b] = sa;
+                accepted += 1;
+            }
+        }
+        accepted
+    }
+
+    /// Characteristic domain size: number of bonds per broken (domain-wall) bond.
+    pub fn domain_length(&self) -> f64 {
+        let broken = self
+            .lattice
+            .bonds
+            .iter()
+            .filter(|b| b.sign * self.spins[b.a] * self.spins[b.b] < 0)
+            .count();
+        if broken == 0 {
+            return self.size as f64;
+        }
+        self.lattice.bonds.len() as f64 / broken as f64
+    }
+
+    /// Runs `sweeps` Kawasaki sweeps and returns `(sweep, domain_length)` every
+    /// `interval` sweeps, for following phase separation at fixed composition.
+    pub fn kawasaki_domain_growth(&mut self, sweeps: usize, interval: usize) -> Vec<(usize, f64)> {
+        let mut growth = vec![(0, self.domain_length())];
+        for t in 1..=sweeps {
+            self.kawasaki_sweep();
+            if t % interval == 0 {
+                growth.push((t, self.domain_length()));
+            }
+        }
+        growth
+    }
+
+    /// Swendsen–Wang multi-cluster update
+    /// - Activates each satisfied bond with probability 1 - exp(-2|J|/T)
+    /// - Labels all clusters and flips each one with probability 1/2
+    /// - Returns the bond configuration and labelling used for the step
+    pub fn swendsen_wang_step(&mut self) -> BondConfiguration {
+        let mut rng = rand::rng();
+        let p_add = self.bond_probabilities();
+        let j = &self.couplings.j;
+        let active_bonds: Vec<bool> = self
+            .lattice
+            .bonds
+            .iter()
+            .map(|b| {
+                j[b.direction] * (b.sign * self.spins[b.a] * self.spins[b.b]) as f64 > 0.0
+                    && rng.random::<f64>() < p_add[b.direction]
+            })
+            .collect();
+        let (labels, cluster_sizes) = self.lattice.label_clusters(&active_bonds);
+
+        let flip: Vec<bool> = (0..cluster_sizes.len())
+            .map(|_| rng.random_bool(0.5))
+            .collect();
+        for (spin, &label) in self.spins.iter_mut().zip(&labels) {
+            if flip[label] {
+                *spin = -*spin;
+            }
+        }
+
+        BondConfiguration {
+            active_bonds,
+            labels,
+            cluster_sizes,
+        }
+    }
+
     pub fn magnetization(&self) -> f64 {
-        self.spins.iter().flatten().map(|&s| s as f64).sum::<f64>() / (self.size * self.size) as f64
+        self.spins.iter().map(|&s| s as f64).sum::<f64>() / self.num_spins() as f64
+    }
+
+    /// Total energy of the `IsingCouplings` Hamiltonian.
+    pub fn energy(&self) -> f64 {
+        let c = &self.couplings;
+        let pair = |b: &Bond| (b.sign * self.spins[b.a] * self.spins[b.b]) as f64;
+        let bonds: f64 = self
+            .lattice
+            .bonds
+            .iter()
+            .map(|b| c.j[b.direction] * pair(b))
+            .sum();
+        let second: f64 = self.second_bonds.iter().map(pair).sum();
+        let zeeman: f64 = (0..self.num_spins())
+            .map(|i| (c.field + self.sublattice_sign(i) * c.staggered_field) * self.spins[i] as f64)
+            .sum();
+        -bonds - c.j2 * second - zeeman
+    }
+
+    pub fn energy_per_spin(&self) -> f64 {
+        self.energy() / self.num_spins() as f64
+    }
+
+    /// Runs `thermalization` sweeps of `step`, then records one measurement
+    /// after each of `measurements` further sweeps.
+    pub fn measure(&mut self, thermalization: usize, measurements: usize) -> IsingObservables {
+        self.measure_with(thermalization, measurements, |model| model.step())
+    }
+
+    /// Like `measure`, but advances the chain between measurements with `update`
+    /// (e.g. `|m| { m.wolff_sweep(); }` near T_c).
+    pub fn measure_with<F>(
+        &mut self,
+        thermalization: usize,
+        measurements: usize,
+        mut update: F,
+    ) -> IsingObservables
+    where
+        F: FnMut(&mut Self),
+    {
+        for _ in 0..thermalization {
+            update(self);
+        }
+        let mut energies = Vec::with_capacity(measurements);
+        let mut magnetizations = Vec::with_capacity(measurements);
+        for _ in 0..measurements {
+            update(self);
+            energies.push(self.energy_per_spin());
+            magnetizations.push(self.magnetization());
+        }
+        IsingObservables::from_series(self.temperature, self.num_spins(), energies, magnetizations)
+    }
+
+    /// Accumulates G(r), S(k) and ξ over `measurements` configurations, advancing
+    /// the chain with `update` as in `measure_with`; needs periodic boundaries.
+    pub fn measure_correlations<F>(
+        &mut self,
+        thermalization: usize,
+        measurements: usize,
+        mut update: F,
+    ) -> Correlations
+    where
+        F: FnMut(&mut Self),
+    {
+        for _ in 0..thermalization {
+            update(self);
+        }
+        let mut correlations = Correlations::new(&self.lattice);
+        for _ in 0..measurements {
+            update(self);
+            let values: Vec<f64> = self.spins.iter().map(|&s| s as f64).collect();
+            correlations.add(&values);
+        }
+        correlations
+    }
+}
+
+impl Replica for IsingModel {
+    fn energy(&self) -> f64 {
+        IsingModel::energy(self)
+    }
+    fn temperature(&self) -> f64 {
+        self.temperature
+    }
+    fn set_temperature(&mut self, temperature: f64) {
+        self.temperature = temperature;
+    }
+    fn sweep(&mut self) {
+        self.step();
     }
 }
 
+/// Runs about `num_spins / <cluster size>` single-cluster moves and returns their number
+/// - The count is fixed before the first move from `mean_cluster_size`, a running mean
+///   that `step` (returning the cluster size) updates afterwards; a zero mean is seeded
+///   with one extra move
+/// - Stopping once `num_spins` spins have flipped instead would depend on the current
+///   configuration and bias the sampled distribution on small lattices
+pub fn cluster_sweep<F>(num_spins: usize, mean_cluster_size: &mut f64, mut step: F) -> usize
+where
+    F: FnMut() -> usize,
+{
+    if *mean_cluster_size == 0.0 {
+        *mean_cluster_size = step() as f64;
+    }
+    let moves = ((num_spins as f64 / *mean_cluster_size).round() as usize).max(1);
+    for _ in 0..moves {
+        let size = step() as f64;
+        *mean_cluster_size += 0.01 * (size - *mean_cluster_size);
+    }
+    moves
+}
+
 pub fn ising_example() {
     let mut model = IsingModel::new(20, 2.0);
     for _ in 0..1000 {
         model.step();
     }
     println!("Magnetization: {}", model.magnetization());
+
+    let obs = model.measure(100, 1000);
+    println!(
+        "Energy per spin: {:.4} ± {:.4}",
+        obs.energy.mean, obs.energy.error
+    );
+    println!(
+        "Specific heat: {:.4} ± {:.4}",
+        obs.specific_heat.mean, obs.specific_heat.error
+    );
+    println!(
+        "Binder cumulant: {:.4} ± {:.4}",
+        obs.binder_cumulant.mean, obs.binder_cumulant.error
+    );
 }
 
 #[test]
@@ -64,3 +807,227 @@ fn test_ising_model() {
     let magnetization = model.magnetization();
     assert!(magnetization.abs() <= 1.0, "Magnetization out of bounds");
 }
+
+#[test]
+fn test_wolff_orders_low_temperature() {
+    let mut model = IsingModel::new(16, 1.0);
+    for _ in 0..50 {
+        let cluster_size = model.wolff_step();
+        assert!((1..=16 * 16).contains(&cluster_size));
+    }
+    // Well below T_c ≈ 2.269 the Wolff dynamics should reach an ordered state.
+    assert!(model.magnetization().abs() > 0.9, "Wolff failed to order");
+}
+
+#[test]
+fn test_wolff_sweep_matches_exact_enumeration() {
+    use crate::physics::exact::ExactEnumeration;
+    // A sweep that stopped once N spins had flipped gave e = -1.596 on 4×4 at
+    // T = 2.5 (exact -1.379) and always ended aligned on the two-spin chain.
+    let temperature = 2.5;
+    for lattice in [
+        Lattice::square(4, Boundary::Periodic),
+        Lattice::hypercubic(1, 2, Boundary::Open),
+    ] {
+        let exact = ExactEnumeration::new(&lattice).at(temperature);
+        let obs = IsingModel::with_lattice(lattice, temperature).measure_with(500, 20_000, |m| {
+            m.wolff_sweep();
+        });
+        let e = obs.energy;
+        assert!(
+            (e.mean - exact.energy).abs() < 5.0 * e.error,
+            "e = {} ± {}, exact {}",
+            e.mean,
+            e.error,
+            exact.energy
+        );
+        let m = obs.abs_magnetization;
+        assert!(
+            (m.mean - exact.abs_magnetization).abs() < 5.0 * m.error,
+            "|m| = {} ± {}, exact {}",
+            m.mean,
+            m.error,
+            exact.abs_magnetization
+        );
+    }
+}
+
+#[test]
+fn test_swendsen_wang_clusters() {
+    let mut model = IsingModel::new(16, 1.5);
+    let mut bonds = model.swendsen_wang_step();
+    for _ in 0..100 {
+        bonds = model.swendsen_wang_step();
+    }
+    assert_eq!(bonds.cluster_sizes.iter().sum::<usize>(), 16 * 16);
+    // Sites joined by an active bond must share a cluster label.
+    for (bond, &active) in model.lattice.bonds.iter().zip(&bonds.active_bonds) {
+        if active {
+            assert_eq!(bonds.labels[bond.a], bonds.labels[bond.b]);
+        }
+    }
+    // In the ordered phase one cluster spans most of the lattice.
+    assert!(bonds.improved_susceptibility() > 0.5 * (16 * 16) as f64);
+}
+
+#[test]
+fn test_ising_on_other_lattices() {
+    // The simple cubic T_c ≈ 4.51, so T = 3 is deep in the ordered phase.
+    let mut cubic = IsingModel::with_lattice(Lattice::simple_cubic(6, Boundary::Periodic), 3.0);
+    for _ in 0..50 {
+        cubic.swendsen_wang_step();
+    }
+    assert!(cubic.magnetization().abs() > 0.8);
+
+    let mut open = IsingModel::with_lattice(Lattice::triangular(8, Boundary::Open), 2.0);
+    open.step();
+    assert!(open.magnetization().abs() <= 1.0);
+}
+
+#[test]
+fn test_ising_observables() {
+    use crate::physics::exact::onsager_energy;
+    let mut model = IsingModel::new(8, 1.5);
+    let obs = model.measure_with(200, 2000, |m| {
+        m.wolff_sweep();
+    });
+    // Deep in the ordered phase: |m| ≈ 1 and U ≈ 2/3, and with ξ far below L = 8
+    // the energy matches Onsager's (long runs agree to 2e-4).
+    let onsager = onsager_energy(1.5);
+    assert!(
+        (obs.energy.mean - onsager).abs() < 4.0 * obs.energy.error,
+        "e = {} ± {}, Onsager {}",
+        obs.energy.mean,
+        obs.energy.error,
+        onsager
+    );
+    assert!(obs.abs_magnetization.mean > 0.95);
+    assert!((obs.binder_cumulant.mean - 2.0 / 3.0).abs() < 0.02);
+    assert!(obs.specific_heat.mean > 0.0 && obs.specific_heat.error > 0.0);
+    assert!(obs.susceptibility.error.is_finite());
+
+    // High temperature: short-range order only, U drops towards 0.
+    let mut hot = IsingModel::new(8, 10.0);
+    let obs = hot.measure(100, 2000);
+    assert!(obs.binder_cumulant.mean < 0.3);
+    assert!((obs.energy.mean - obs.energy_series.iter().sum::<f64>() / 2000.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_kawasaki_conserves_magnetization() {
+    let mut model = IsingModel::new(32, 1.0);
+    model.randomize_with_magnetization(0.0);
+    assert_eq!(model.spins.iter().sum::<i32>(), 0);
+
+    let growth = model.kawasaki_domain_growth(200, 50);
+    assert_eq!(model.spins.iter().sum::<i32>(), 0);
+    assert_eq!(growth.len(), 5);
+    // Below T_c a quenched 50/50 mixture phase-separates, so domains grow.
+    assert!(growth[4].1 > 1.5 * growth[0].1, "growth: {:?}", growth);
+
+    // At T → 0 only exchanges that do not raise the energy may be accepted.
+    let mut probe = IsingModel::new(8, 1e-9);
+    probe.randomize_with_magnetization(0.5);
+    let before = probe.energy();
+    probe.kawasaki_sweep();
+    assert!(probe.energy() <= before);
+}
+
+#[test]
+fn test_update_rules_and_orders_agree() {
+    let reference = IsingModel::new(8, 2.5).measure(200, 3000).energy;
+    let variants = [
+        (UpdateRule::Glauber, SiteOrder::Random),
+        (UpdateRule::Glauber, SiteOrder::Sequential),
+        (UpdateRule::Metropolis, SiteOrder::Checkerboard),
+    ];
+    for (rule, order) in variants {
+        let mut model = IsingModel::new(8, 2.5);
+        model.update_rule = rule;
+        model.site_order = order;
+        let e = model.measure(200, 3000).energy;
+        let sigma = (e.error.powi(2) + reference.error.powi(2)).sqrt();
+        assert!(
+            (e.mean - reference.mean).abs() < 5.0 * sigma,
+            "{:?}/{:?}: {} vs {}",
+            rule,
+            order,
+            e.mean,
+            reference.mean
+        );
+    }
+}
+
+#[test]
+fn test_glauber_zero_temperature() {
+    // A single domain on a ring: the two walls cost nothing to move (ΔE = 0), so
+    // Glauber dynamics at T = 0 must still shift them, while the energy never rises.
+    let mut model = IsingModel::with_lattice(Lattice::hypercubic(1, 32, Boundary::Periodic), 0.0);
+    model.update_rule = UpdateRule::Glauber;
+    model.spins = (0..32).map(|i| if i < 16 { 1 } else { -1 }).collect();
+    let initial = model.spins.clone();
+    let energy = model.energy();
+    for _ in 0..20 {
+        model.step();
+        assert!(model.energy() <= energy);
+    }
+    assert_ne!(model.spins, initial, "Domain walls never moved at T = 0");
+}
+
+#[test]
+fn test_parallel_step_matches_serial() {
+    let serial = IsingModel::new(16, 2.5).measure(200, 3000).energy;
+    let mut model = IsingModel::new(16, 2.5);
+    let parallel = model.measure_with(200, 3000, |m| m.parallel_step(4)).energy;
+    let sigma = (serial.error.powi(2) + parallel.error.powi(2)).sqrt();
+    assert!(
+        (serial.mean - parallel.mean).abs() < 5.0 * sigma,
+        "{} vs {}",
+        serial.mean,
+        parallel.mean
+    );
+}
+
+#[test]
+fn test_couplings_energy_differences() {
+    let mut rng = rand::rng();
+    for second_neighbors in [SecondNeighbors::Diagonal, SecondNeighbors::Axial(0)] {
+        let lattice = Lattice::square(6, Boundary::Antiperiodic);
+        let mut model = IsingModel::with_lattice(lattice, 1.7).with_couplings(IsingCouplings {
+            j: vec![1.0, 0.5],
+            j2: -0.6,
+            second_neighbors,
+            field: 0.3,
+            staggered_field: -0.2,
+        });
+        for rule in [UpdateRule::Metropolis, UpdateRule::Glauber] {
+            model.update_rule = rule;
+            model.refresh_table();
+            let table = model.table.as_ref().unwrap();
+            for _ in 0..100 {
+                let site = rng.random_range(0..model.num_spins());
+                let delta_e = model.delta_energy(site);
+                let p = table.probabilities[model.table_key(table, site)];
+                assert!((p - acceptance(rule, delta_e, 1.7)).abs() < 1e-12);
+                let before = model.energy();
+                model.spins[site] = -model.spins[site];
+                assert!((model.energy() - before - delta_e).abs() < 1e-9);
+            }
+        }
+    }
+
+    // A strong staggered field orders an antiferromagnet at low temperature.
+    let mut model = IsingModel::new(8, 0.5);
+    let mut couplings = IsingCouplings::ferromagnetic(&model.lattice);
+    couplings.j = vec![-1.0, -1.0];
+    couplings.staggered_field = 0.5;
+    model.set_couplings(couplings);
+    for _ in 0..200 {
+        model.step();
+    }
+    let staggered: f64 = (0..64)
+        .map(|i| model.sublattice_sign(i) * model.spins[i] as f64)
+        .sum::<f64>()
+        / 64.0;
+    assert!(staggered > 0.9, "staggered magnetization {}", staggered);
+}
@@ -0,0 +1,494 @@
+use crate::models::lattice::Lattice;
+use crate::physics::ising_model::IsingObservables;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+/// Multi-spin coded Ising engines
+/// - Spins are bits (1 = up) packed into u64 words; 64 spins are updated per word operation
+/// - `MultiSpinIsing`: one large square lattice, checkerboard-packed so every word holds
+///   64 spins of the same sublattice (L = 4096 needs 2 MiB)
+/// - `ReplicaIsing`: 64 independent replicas on any `Lattice`, bit r of a site's word is
+///   the spin of replica r
+/// - Acceptance probabilities are precomputed per number of antiparallel neighbours and
+///   turned into random bit masks, so every bit gets its own Bernoulli decision
+pub struct MultiSpinIsing {
+    pub size: usize,
+    pub temperature: f64,
+    /// Words per half-row (L / 2 bits, rounded up to whole words).
+    words_per_row: usize,
+    /// `black[y * words_per_row + w]`, bit k ↔ site (x = 2k + y % 2, y)
+    black: Vec<u64>,
+    /// `white[y * words_per_row + w]`, bit k ↔ site (x = 2k + 1 - y % 2, y)
+    white: Vec<u64>,
+    acceptance: Vec<BernoulliMask>,
+    rng: SmallRng,
+}
+
+pub struct ReplicaIsing {
+    pub temperature: f64,
+    pub lattice: Lattice,
+    /// `spins[site]`, bit r is the spin of replica r
+    pub spins: Vec<u64>,
+    /// `acceptance[z][a]` for a site with z neighbours of which a are antiparallel
+    acceptance: Vec<Vec<BernoulliMask>>,
+    rng: SmallRng,
+}
+
+/// Generates random words whose bits are independently set with probability p,
+/// built from the binary expansion of p (32 bits of precision).
+#[derive(Clone, Debug)]
+struct BernoulliMask {
+    digits: Vec<bool>,
+    always: bool,
+}
+
+impl BernoulliMask {
+    fn new(p: f64) -> Self {
+        let p = p.clamp(0.0, 1.0);
+        let mut digits = Vec::new();
+        let mut rest = p;
+        for _ in 0..32 {
+            rest *= 2.0;
+            digits.push(rest >= 1.0);
+            if rest >= 1.0 {
+                rest -= 1.0;
+            }
+        }
+        // Trailing zeros contribute nothing once processed from the least significant end.
+        while digits.last() == Some(&false) {
+            digits.pop();
+        }
+        Self {
+            digits,
+            always: p >= 1.0,
+        }
+    }
+
+    fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        if self.always {
+            return !0;
+        }
+        let mut mask = 0;
+        for &digit in self.digits.iter().rev() {
+            let r: u64 = rng.random();
+            mask = if digit { r | mask } else { r & mask };
+        }
+        mask
+    }
+}
+
+/// Bit-sliced counter: `bits[k]` holds bit k of a per-lane count.
+struct BitCounter {
+    bits: [u64; 4],
+}
+
+impl BitCounter {
+    fn new() -> Self {
+        Self { bits: [0; 4] }
+    }
+
+    fn add(&mut self, x: u64) {
+        let mut carry = x;
+        for b in self.bits.iter_mut() {
+            let next = *b & carry;
+            *b ^= carry;
+            carry = next;
+        }
+    }
+
+    /// Lanes whose count equals `value`.
+    fn equals(&self, value: usize) -> u64 {
+        self.bits.iter().enumerate().fold(!0, |acc, (k, &b)| {
+            acc & if value >> k & 1 == 1 { b } else { !b }
+        })
+    }
+}
+
+/// Mask of the bits of word `w` that belong to a half-row of `half` bits.
+fn valid_mask(half: usize, w: usize) -> u64 {
+    let rem = half % 64;
+    if w == half.div_ceil(64) - 1 && rem != 0 {
+        (1u64 << rem) - 1
+    } else {
+        !0
+    }
+}
+
+/// Word whose bit k holds bit k - 1 (cyclically) of a half-row.
+fn previous_bits(row: &[u64], half: usize, w: usize) -> u64 {
+    let carry = if w == 0 {
+        (row[row.len() - 1] >> ((half - 1) % 64)) & 1
+    } else {
+        row[w - 1] >> 63
+    };
+    ((row[w] << 1) | carry) & valid_mask(half, w)
+}
+
+/// Word whose bit k holds bit k + 1 (cyclically) of a half-row.
+fn next_bits(row: &[u64], half: usize, w: usize) -> u64 {
+    let (carry, top) = if w == row.len() - 1 {
+        (row[0] & 1, (half - 1) % 64)
+    } else {
+        (row[w + 1] & 1, 63)
+    };
+    (row[w] >> 1) | (carry << top)
+}
+
+impl MultiSpinIsing {
+    /// Periodic L×L square lattice with random spins; L must be even.
+    pub fn new(size: usize, temperature: f64) -> Self {
+        assert!(
+            size >= 4 && size.is_multiple_of(2),
+            "Lattice size must be even and at least 4"
+        );
+        let words_per_row = (size / 2).div_ceil(64);
+        let mut rng = SmallRng::from_rng(&mut rand::rng());
+        let mut model = Self {
+            size,
+            temperature,
+            words_per_row,
+            black: (0..size * words_per_row).map(|_| rng.random()).collect(),
+            white: (0..size * words_per_row).map(|_| rng.random()).collect(),
+            acceptance: Vec::new(),
+            rng,
+        };
+        for y in 0..size {
+            for w in 0..words_per_row {
+                let mask = model.valid_mask(w);
+                model.black[y * words_per_row + w] &= mask;
+                model.white[y * words_per_row + w] &= mask;
+            }
+        }
+        model.set_temperature(temperature);
+        model
+    }
+
+    /// Changes the temperature and rebuilds the acceptance table for
+    /// a = 0..=4 antiparallel neighbours (a ≥ 2 always flips).
+    pub fn set_temperature(&mut self, temperature: f64) {
+        self.temperature = temperature;
+        self.acceptance = (0..=4)
+            .map(|a| BernoulliMask::new((-2.0 * (4.0 - 2.0 * a as f64) / temperature).exp()))
+            .collect();
+    }
+
+    pub fn num_spins(&self) -> usize {
+        self.size * self.size
+    }
+
+    fn half(&self) -> usize {
+        self.size / 2
+    }
+
+    fn valid_mask(&self, w: usize) -> u64 {
+        valid_mask(self.half(), w)
+    }
+
+    /// Flips the words of one sublattice; `black` selects which.
+    fn update_sublattice(&mut self, black: bool) {
+        let l = self.size;
+        let half = self.half();
+        let wpr = self.words_per_row;
+        let Self {
+            black: black_words,
+            white: white_words,
+            acceptance,
+            rng,
+            ..
+        } = self;
+        let (targets, others) = if black {
+            (black_words, &*white_words)
+        } else {
+            (white_words, &*black_words)
+        };
+        for y in 0..l {
+            let up = ((y + l - 1) % l) * wpr;
+            let down = ((y + 1) % l) * wpr;
+            let row = &others[y * wpr..(y + 1) * wpr];
+            // Black sites on even rows and white sites on odd rows see their
+            // second horizontal neighbour at k - 1, the others at k + 1.
+            let use_previous = black == y.is_multiple_of(2);
+            for w in 0..wpr {
+                let s = targets[y * wpr + w];
+                let side = if use_previous {
+                    previous_bits(row, half, w)
+                } else {
+                    next_bits(row, half, w)
+                };
+                let mut counter = BitCounter::new();
+                for n in [row[w], side, others[up + w], others[down + w]] {
+                    counter.add(s ^ n);
+                }
+                let mut flip = 0;
+                for (a, mask) in acceptance.iter().enumerate() {
+                    flip |= counter.equals(a) & mask.sample(rng);
+                }
+                targets[y * wpr + w] ^= flip & valid_mask(half, w);
+            }
+        }
+    }
+
+    /// One checkerboard sweep: all black sites, then all white sites.
+    pub fn step(&mut self) {
+        self.update_sublattice(true);
+        self.update_sublattice(false);
+    }
+
+    pub fn magnetization(&self) -> f64 {
+        let up: u64 = self
+            .black
+            .iter()
+            .chain(&self.white)
+            .map(|w| w.count_ones() as u64)
+            .sum();
+        (2.0 * up as f64 - self.num_spins() as f64) / self.num_spins() as f64
+    }
+
+    /// Total energy E = -J Σ_<ij> s_i s_j, counting every bond from its black end.
+    pub fn energy(&self) -> f64 {
+        let l = self.size;
+        let wpr = self.words_per_row;
+        let mut antiparallel = 0u64;
+        for y in 0..l {
+            let row = &self.white[y * wpr..(y + 1) * wpr];
+            let up = ((y + l - 1) % l) * wpr;
+            let down = ((y + 1) % l) * wpr;
+            for w in 0..wpr {
+                let s = self.black[y * wpr + w];
+                let side = if y.is_multiple_of(2) {
+                    previous_bits(row, self.half(), w)
+                } else {
+                    next_bits(row, self.half(), w)
+                };
+                for n in [row[w], side, self.white[up + w], self.white[down + w]] {
+                    antiparallel += ((s ^ n) & self.valid_mask(w)).count_ones() as u64;
+                }
+            }
+        }
+        let bonds = 2 * self.num_spins() as i64;
+        -((bonds - 2 * antiparallel as i64) as f64)
+    }
+
+    pub fn energy_per_spin(&self) -> f64 {
+        self.energy() / self.num_spins() as f64
+    }
+
+    /// Spins as ±1 in `IsingModel` site order (`y * L + x`).
+    pub fn to_spins(&self) -> Vec<i32> {
+        let l = self.size;
+        let wpr = self.words_per_row;
+        let mut spins = vec![0; l * l];
+        for y in 0..l {
+            for k in 0..self.half() {
+                let (w, bit) = (k / 64, k % 64);
+                let p = y % 2;
+                let b = (self.black[y * wpr + w] >> bit) & 1;
+                let wh = (self.white[y * wpr + w] >> bit) & 1;
+                spins[y * l + 2 * k + p] = if b == 1 { 1 } else { -1 };
+                spins[y * l + 2 * k + 1 - p] = if wh == 1 { 1 } else { -1 };
+            }
+        }
+        spins
+    }
+
+    /// Same measurement protocol and output as `IsingModel::measure`.
+    pub fn measure(&mut self, thermalization: usize, measurements: usize) -> IsingObservables {
+        for _ in 0..thermalization {
+            self.step();
+        }
+        let mut energies = Vec::with_capacity(measurements);
+        let mut magnetizations = Vec::with_capacity(measurements);
+        for _ in 0..measurements {
+            self.step();
+            energies.push(self.energy_per_spin());
+            magnetizations.push(self.magnetization());
+        }
+        IsingObservables::from_series(self.temperature, self.num_spins(), energies, magnetizations)
+    }
+}
+
+impl ReplicaIsing {
+    pub const REPLICAS: usize = 64;
+
+    /// 64 independent random replicas on `lattice` (coordination at most 15).
+    pub fn new(lattice: Lattice, temperature: f64) -> Self {
+        assert!(
+            lattice.neighbors.iter().all(|n| n.len() < 16),
+            "Coordination number must be below 16"
+        );
+        let mut rng = SmallRng::from_rng(&mut rand::rng());
+        let spins = (0..lattice.num_sites).map(|_| rng.random()).collect();
+        let mut model = Self {
+            temperature,
+            lattice,
+            spins,
+            acceptance: Vec::new(),
+            rng,
+        };
+        model.set_temperature(temperature);
+        model
+    }
+
+    pub fn set_temperature(&mut self, temperature: f64) {
+        self.temperature = temperature;
+        let max_z = self
+            .lattice
+            .neighbors
+            .iter()
+            .map(|n| n.len())
+            .max()
+            .unwrap_or(0);
+        self.acceptance = (0..=max_z)
+            .map(|z| {
+                (0..=z)
+                    .map(|a| {
+                        let delta_e = 2.0 * (z as f64 - 2.0 * a as f64);
+                        BernoulliMask::new((-delta_e / temperature).exp())
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    /// Word whose bits mark replicas where the bond from a spin word `s` to
+    /// `other` (with sign `sign`) is unsatisfied.
+    fn antiparallel(&self, s: u64, other: usize, sign: i32) -> u64 {
+        let aligned = if sign < 0 {
+            !self.spins[other]
+        } else {
+            self.spins[other]
+        };
+        s ^ aligned
+    }
+
+    /// One sequential sweep over all sites, updating all 64 replicas at once.
+    pub fn step(&mut self) {
+        for site in 0..self.lattice.num_sites {
+            let s = self.spins[site];
+            let mut counter = BitCounter::new();
+            for n in &self.lattice.neighbors[site] {
+                counter.add(self.antiparallel(s, n.site, n.sign));
+            }
+            let z = self.lattice.neighbors[site].len();
+            let mut flip = 0;
+            for a in 0..=z {
+                flip |= counter.equals(a) & self.acceptance[z][a].sample(&mut self.rng);
+            }
+            self.spins[site] ^= flip;
+        }
+    }
+
+    pub fn magnetizations(&self) -> Vec<f64> {
+        let n = self.lattice.num_sites as f64;
+        (0..Self::REPLICAS)
+            .map(|r| {
+                let up = self.spins.iter().filter(|&&w| (w >> r) & 1 == 1).count();
+                (2.0 * up as f64 - n) / n
+            })
+            .collect()
+    }
+
+    pub fn energies_per_spin(&self) -> Vec<f64> {
+        let mut antiparallel = [0u32; 64];
+        for b in &self.lattice.bonds {
+            let word = self.antiparallel(self.spins[b.a], b.b, b.sign);
+            for (r, count) in antiparallel.iter_mut().enumerate() {
+                *count += ((word >> r) & 1) as u32;
+            }
+        }
+        let bonds = self.lattice.bonds.len() as f64;
+        let n = self.lattice.num_sites as f64;
+        antiparallel
+            .iter()
+            .map(|&a| -(bonds - 2.0 * a as f64) / n)
+            .collect()
+    }
+
+    /// Runs the usual thermalisation / measurement protocol on all replicas and
+    /// returns one `IsingObservables` per replica.
+    pub fn measure(&mut self, thermalization: usize, measurements: usize) -> Vec<IsingObservables> {
+        for _ in 0..thermalization {
+            self.step();
+        }
+        let mut energies: Vec<Vec<f64>> = (0..Self::REPLICAS)
+            .map(|_| Vec::with_capacity(measurements))
+            .collect();
+        let mut magnetizations: Vec<Vec<f64>> = (0..Self::REPLICAS)
+            .map(|_| Vec::with_capacity(measurements))
+            .collect();
+        for _ in 0..measurements {
+            self.step();
+            for (series, e) in energies.iter_mut().zip(self.energies_per_spin()) {
+                series.push(e);
+            }
+            for (series, m) in magnetizations.iter_mut().zip(self.magnetizations()) {
+                series.push(m);
+            }
+        }
+        energies
+            .into_iter()
+            .zip(magnetizations)
+            .map(|(e, m)| {
+                IsingObservables::from_series(self.temperature, self.lattice.num_sites, e, m)
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_bernoulli_mask_probability() {
+    let mut rng = rand::rng();
+    let mask = BernoulliMask::new(0.3);
+    let ones: u32 = (0..2000).map(|_| mask.sample(&mut rng).count_ones()).sum();
+    let p = ones as f64 / (2000.0 * 64.0);
+    assert!((p - 0.3).abs() < 0.01, "p = {}", p);
+}
+
+#[test]
+fn test_multispin_matches_ising_model() {
+    use crate::physics::ising_model::IsingModel;
+    // L = 12 exercises partial words and the cyclic carries.
+    let mut fast = MultiSpinIsing::new(12, 2.5);
+    let mut reference = IsingModel::new(12, 2.5);
+    reference.spins = fast.to_spins();
+    assert_eq!(fast.energy(), reference.energy());
+    assert_eq!(fast.magnetization(), reference.magnetization());
+
+    let a = fast.measure(200, 4000).energy;
+    let b = reference.measure(200, 4000).energy;
+    let sigma = (a.error.powi(2) + b.error.powi(2)).sqrt();
+    assert!(
+        (a.mean - b.mean).abs() < 5.0 * sigma,
+        "{} vs {}",
+        a.mean,
+        b.mean
+    );
+
+    let mut big = MultiSpinIsing::new(256, 1.5);
+    for _ in 0..10 {
+        big.step();
+    }
+    let mut check = IsingModel::new(256, 1.5);
+    check.spins = big.to_spins();
+    assert_eq!(big.energy(), check.energy());
+}
+
+#[test]
+fn test_replicas_are_independent_and_equilibrate() {
+    use crate::models::lattice::Boundary;
+    use crate::physics::ising_model::IsingModel;
+    let mut replicas = ReplicaIsing::new(Lattice::square(8, Boundary::Periodic), 2.5);
+    let obs = replicas.measure(100, 500);
+    let mean = obs.iter().map(|o| o.energy.mean).sum::<f64>() / 64.0;
+    let reference = IsingModel::new(8, 2.5).measure(200, 4000).energy;
+    assert!(
+        (mean - reference.mean).abs() < 5.0 * reference.error + 0.01,
+        "{} vs {}",
+        mean,
+        reference.mean
+    );
+    let spread = obs
+        .iter()
+        .map(|o| (o.energy.mean - mean).abs())
+        .fold(0.0, f64::max);
+    assert!(spread > 0.0);
+}
@@ -0,0 +1,296 @@
+use crate::algorithms::parallel_tempering::Replica;
+use crate::models::lattice::{Boundary, Lattice};
+use crate::physics::ising_model::{BondConfiguration, IsingObservables};
+use rand::Rng;
+/// q-state Potts model, H = -J Σ_<ij> δ(s_i, s_j)
+/// - `coupling` J > 0 is ferromagnetic, J < 0 antiferromagnetic
+/// - Metropolis, heat-bath and (ferromagnetic) Swendsen–Wang updates
+/// - Order parameter m = (q max_k n_k / N - 1) / (q - 1), 0 when disordered and 1 when ordered
+pub struct PottsModel {
+    pub q: usize,
+    pub coupling: f64,
+    pub temperature: f64,
+    pub lattice: Lattice,
+    pub spins: Vec<usize>,
+}
+
+/// Measurement phase of a `PottsModel`
+/// - `thermo` holds energy / order-parameter statistics in the same form as the Ising model
+/// - `energy_histogram[k]` counts measurements with k satisfied bonds (E = -J k); a double
+///   peak signals a first-order transition
+pub struct PottsObservables {
+    pub thermo: IsingObservables,
+    pub energy_histogram: Vec<usize>,
+}
+
+impl PottsModel {
+    /// Square L×L lattice with periodic boundaries.
+    pub fn new(size: usize, q: usize, coupling: f64, temperature: f64) -> Self {
+        Self::with_lattice(
+            Lattice::square(size, Boundary::Periodic),
+            q,
+            coupling,
+            temperature,
+        )
+    }
+
+    pub fn with_lattice(lattice: Lattice, q: usize, coupling: f64, temperature: f64) -> Self {
+        assert!(q >= 2, "Potts model needs at least two states");
+        let mut rng = rand::rng();
+        let spins = (0..lattice.num_sites)
+            .map(|_| rng.random_range(0..q))
+            .collect();
+        Self {
+            q,
+            coupling,
+            temperature,
+            lattice,
+            spins,
+        }
+    }
+
+    pub fn num_spins(&self) -> usize {
+        self.lattice.num_sites
+    }
+
+    /// Number of neighbours of `site` in each of the q states.
+    fn neighbor_counts(&self, site: usize) -> Vec<usize> {
+        let mut counts = vec![0; self.q];
+        for n in &self.lattice.neighbors[site] {
+            counts[self.spins[n.site]] += 1;
+        }
+        counts
+    }
+
+    /// N random-site Metropolis moves, each proposing one of the other q - 1 states.
+    pub fn metropolis_sweep(&mut self) {
+        let mut rng = rand::rng();
+        for _ in 0..self.num_spins() {
+            let site = rng.random_range(0..self.num_spins());
+            let old = self.spins[site];
+            let new = (old + rng.random_range(1..self.q)) % self.q;
+            let counts = self.neighbor_counts(site);
+            let delta_e = -self.coupling * (counts[new] as f64 - counts[old] as f64);
+            if delta_e <= 0.0 || rng.random::<f64>() < (-delta_e / self.temperature).exp() {
+                self.spins[site] = new;
+            }
+        }
+    }
+
+    /// N random-site heat-bath moves: the new state is drawn with weight
+    /// exp(J n_k / T), independent of the old one.
+    pub fn heat_bath_sweep(&mut self) {
+        let mut rng = rand::rng();
+        let mut weights = vec![0.0; self.q];
+        for _ in 0..self.num_spins() {
+            let site = rng.random_range(0..self.num_spins());
+            let counts = self.neighbor_counts(site);
+            // Shift by the count with the largest exponent J c, so the top weight is
+            // exactly 1 and the sum cannot underflow to 0 at low T.
+            let shift = if self.coupling > 0.0 {
+                *counts.iter().max().unwrap()
+            } else {
+                *counts.iter().min().unwrap()
+            } as f64;
+            for (w, &c) in weights.iter_mut().zip(&counts) {
+                *w = (self.coupling * (c as f64 - shift) / self.temperature).exp();
+            }
+            let mut r = rng.random::<f64>() * weights.iter().sum::<f64>();
+            let mut new = self.q - 1;
+            for (k, &w) in weights.iter().enumerate() {
+                if r < w {
+                    new = k;
+                    break;
+                }
+                r -= w;
+            }
+            self.spins[site] = new;
+        }
+    }
+
+    /// Swendsen–Wang update: equal-state bonds are activated with probability
+    /// 1 - exp(-J/T) and every cluster is assigned a uniformly random new state.
+    pub fn swendsen_wang_step(&mut self) -> BondConfiguration {
+        assert!(
+            self.coupling > 0.0,
+            "Swendsen–Wang requires a ferromagnetic coupling"
+        );
+        let mut rng = rand::rng();
+        let p_add = 1.0 - (-self.coupling / self.temperature).exp();
+        let active_bonds: Vec<bool> = self
+            .lattice
+            .bonds
+            .iter()
+            .map(|b| self.spins[b.a] == self.spins[b.b] && rng.random::<f64>() < p_add)
+            .collect();
+        let (labels, cluster_sizes) = self.lattice.label_clusters(&active_bonds);
+
+        let new_states: Vec<usize> = (0..cluster_sizes.len())
+            .map(|_| rng.random_range(0..self.q))
+            .collect();
+        for (spin, &label) in self.spins.iter_mut().zip(&labels) {
+            *spin = new_states[label];
+        }
+
+        BondConfiguration {
+            active_bonds,
+            labels,
+            cluster_sizes,
+        }
+    }
+
+    pub fn satisfied_bonds(&self) -> usize {
+        self.lattice
+            .bonds
+            .iter()
+            .filter(|b| self.spins[b.a] == self.spins[b.b])
+            .count()
+    }
+
+    pub fn energy(&self) -> f64 {
+        -self.coupling * self.satisfied_bonds() as f64
+    }
+
+    pub fn energy_per_spin(&self) -> f64 {
+        self.energy() / self.num_spins() as f64
+    }
+
+    pub fn order_parameter(&self) -> f64 {
+        let mut counts = vec![0; self.q];
+        for &s in &self.spins {
+            counts[s] += 1;
+        }
+        let max_fraction = *counts.iter().max().unwrap() as f64 / self.num_spins() as f64;
+        (self.q as f64 * max_fraction - 1.0) / (self.q as f64 - 1.0)
+    }
+
+    /// Runs `thermalization` updates, then records energy, order parameter and
+    /// the energy histogram after each of `measurements` further updates.
+    pub fn measure_with<F>(
+        &mut self,
+        thermalization: usize,
+        measurements: usize,
+        mut update: F,
+    ) -> PottsObservables
+    where
+        F: FnMut(&mut Self),
+    {
+        for _ in 0..thermalization {
+            update(self);
+        }
+        let mut energies = Vec::with_capacity(measurements);
+        let mut order = Vec::with_capacity(measurements);
+        let mut energy_histogram = vec![0; self.lattice.bonds.len() + 1];
+        for _ in 0..measurements {
+            update(self);
+            let satisfied = self.satisfied_bonds();
+            energy_histogram[satisfied] += 1;
+            energies.push(-self.coupling * satisfied as f64 / self.num_spins() as f64);
+            order.push(self.order_parameter());
+        }
+        PottsObservables {
+            thermo: IsingObservables::from_series(
+                self.temperature,
+                self.num_spins(),
+                energies,
+                order,
+            ),
+            energy_histogram,
+        }
+    }
+}
+
+impl Replica for PottsModel {
+    fn energy(&self) -> f64 {
+        PottsModel::energy(self)
+    }
+    fn temperature(&self) -> f64 {
+        self.temperature
+    }
+    fn set_temperature(&mut self, temperature: f64) {
+        self.temperature = temperature;
+    }
+    fn sweep(&mut self) {
+        self.metropolis_sweep();
+    }
+}
+
+/// Critical temperature of the ferromagnetic square-lattice Potts model, J / ln(1 + √q).
+/// The transition is continuous for q ≤ 4 and first order above.
+pub fn square_lattice_critical_temperature(q: usize, coupling: f64) -> f64 {
+    coupling / (1.0 + (q as f64).sqrt()).ln()
+}
+
+pub fn potts_example() {
+    for q in [2, 3, 10] {
+        let tc = square_lattice_critical_temperature(q, 1.0);
+        let mut model = PottsModel::new(16, q, 1.0, tc);
+        let obs = model.measure_with(200, 1000, |m| {
+            m.swendsen_wang_step();
+        });
+        println!(
+            "Potts q = {} at T_c = {:.4}: e = {:.4} ± {:.4}, m = {:.4} ± {:.4}",
+            q,
+            tc,
+            obs.thermo.energy.mean,
+            obs.thermo.energy.error,
+            obs.thermo.magnetization.mean,
+            obs.thermo.magnetization.error
+        );
+    }
+}
+
+#[test]
+fn test_potts_updates_agree() {
+    // q = 3 at T = 1.5 > T_c ≈ 0.995: all three updates sample the same ensemble.
+    let thermalization = 200;
+    let measurements = 2000;
+    let metropolis =
+        PottsModel::new(8, 3, 1.0, 1.5)
+            .measure_with(thermalization, measurements, |m| m.metropolis_sweep());
+    let heat_bath =
+        PottsModel::new(8, 3, 1.0, 1.5)
+            .measure_with(thermalization, measurements, |m| m.heat_bath_sweep());
+    let sw = PottsModel::new(8, 3, 1.0, 1.5).measure_with(thermalization, measurements, |m| {
+        m.swendsen_wang_step();
+    });
+    let e = sw.thermo.energy;
+    for other in [&metropolis, &heat_bath] {
+        let diff = (other.thermo.energy.mean - e.mean).abs();
+        let sigma = (other.thermo.energy.error.powi(2) + e.error.powi(2)).sqrt();
+        assert!(diff < 5.0 * sigma + 1e-3, "energies differ by {}", diff);
+    }
+    assert_eq!(sw.energy_histogram.iter().sum::<usize>(), measurements);
+}
+
+#[test]
+fn test_potts_ordering() {
+    let mut ferro = PottsModel::new(8, 4, 1.0, 0.5);
+    for _ in 0..50 {
+        ferro.swendsen_wang_step();
+    }
+    assert!(ferro.order_parameter() > 0.9);
+
+    // Antiferromagnetic q = 3 on the bipartite square lattice at low T:
+    // almost no bond connects equal states.
+    let mut afm = PottsModel::new(8, 3, -1.0, 0.2);
+    for _ in 0..200 {
+        afm.heat_bath_sweep();
+    }
+    assert!(afm.satisfied_bonds() < afm.lattice.bonds.len() / 10);
+}
+
+#[test]
+fn test_antiferromagnetic_heat_bath_low_temperature() {
+    // q = 2 antiferromagnet at T = 0.001, where every exp(J c / T) with c ≥ 1 underflows:
+    // the heat bath must still pick the least-satisfied state and break ties evenly,
+    // so the quench reaches (nearly) the Néel state with both states equally occupied.
+    let mut model = PottsModel::new(16, 2, -1.0, 0.001);
+    for _ in 0..200 {
+        model.heat_bath_sweep();
+    }
+    let satisfied = model.satisfied_bonds() as f64 / model.lattice.bonds.len() as f64;
+    let ones = model.spins.iter().filter(|&&s| s == 1).count() as f64 / 256.0;
+    assert!(satisfied < 0.1, "satisfied bond fraction {}", satisfied);
+    assert!((ones - 0.5).abs() < 0.1, "state-1 fraction {}", ones);
+}
@@ -0,0 +1,285 @@
+use crate::algorithms::parallel_tempering::Replica;
+use crate::diagnostics::{jackknife, Estimate};
+use crate::models::lattice::Lattice;
+use rand::Rng;
+/// Ising model with quenched disorder, H = -Σ_<ij> J_ij s_i s_j - Σ_i h_i s_i
+/// - `couplings[b]` is the coupling on lattice bond b, `fields[i]` the field on site i
+/// - Edwards–Anderson ±J and Gaussian spin glasses, and the random-field Ising model
+/// - `replica` copies the disorder with fresh spins for overlap measurements
+#[derive(Clone)]
+pub struct DisorderedIsing {
+    pub temperature: f64,
+    pub lattice: Lattice,
+    pub couplings: Vec<f64>,
+    pub fields: Vec<f64>,
+    pub spins: Vec<i32>,
+}
+
+/// Disorder-averaged spin-glass observables from `disorder_average`
+/// - `overlap_distribution[k]` is the density P(q) on bin k of `bins` equal bins over [-1, 1]
+/// - `susceptibility` is χ_SG = N [<q²>], `binder_ratio` g = (3 - [<q⁴>] / [<q²>]²) / 2
+/// - Errors come from sample-to-sample fluctuations (jackknife over realisations)
+pub struct SpinGlassObservables {
+    pub realisations: usize,
+    pub overlap_distribution: Vec<f64>,
+    pub q2: Estimate,
+    pub q4: Estimate,
+    pub susceptibility: Estimate,
+    pub binder_ratio: Estimate,
+}
+
+/// Standard normal deviate via the Box–Muller transform.
+pub fn gaussian<R: Rng>(rng: &mut R) -> f64 {
+    let u1: f64 = 1.0 - rng.random::<f64>();
+    let u2: f64 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+impl DisorderedIsing {
+    /// Arbitrary per-bond couplings and per-site fields on `lattice`.
+    pub fn new(lattice: Lattice, temperature: f64, couplings: Vec<f64>, fields: Vec<f64>) -> Self {
+        assert_eq!(
+            couplings.len(),
+            lattice.bonds.len(),
+            "One coupling per bond"
+        );
+        assert_eq!(fields.len(), lattice.num_sites, "One field per site");
+        let mut rng = rand::rng();
+        let spins = (0..lattice.num_sites)
+            .map(|_| if rng.random_bool(0.5) { 1 } else { -1 })
+            .collect();
+        Self {
+            temperature,
+            lattice,
+            couplings,
+            fields,
+            spins,
+        }
+    }
+
+    /// Edwards–Anderson ±J spin glass: J_ij = ±1 with equal probability.
+    pub fn edwards_anderson_pm_j(lattice: Lattice, temperature: f64) -> Self {
+        let mut rng = rand::rng();
+        let couplings = (0..lattice.bonds.len())
+            .map(|_| if rng.random_bool(0.5) { 1.0 } else { -1.0 })
+            .collect();
+        let fields = vec![0.0; lattice.num_sites];
+        Self::new(lattice, temperature, couplings, fields)
+    }
+
+    /// Edwards–Anderson spin glass with standard normal couplings.
+    pub fn edwards_anderson_gaussian(lattice: Lattice, temperature: f64) -> Self {
+        let mut rng = rand::rng();
+        let couplings = (0..lattice.bonds.len())
+            .map(|_| gaussian(&mut rng))
+            .collect();
+        let fields = vec![0.0; lattice.num_sites];
+        Self::new(lattice, temperature, couplings, fields)
+    }
+
+    /// Random-field Ising model: J = 1 and Gaussian fields of standard deviation `strength`.
+    pub fn random_field(lattice: Lattice, temperature: f64, strength: f64) -> Self {
+        let mut rng = rand::rng();
+        let couplings = vec![1.0; lattice.bonds.len()];
+        let fields = (0..lattice.num_sites)
+            .map(|_| strength * gaussian(&mut rng))
+            .collect();
+        Self::new(lattice, temperature, couplings, fields)
+    }
+
+    /// Same disorder, independent random spins.
+    pub fn replica(&self) -> Self {
+        Self::new(
+            self.lattice.clone(),
+            self.temperature,
+            self.couplings.clone(),
+            self.fields.clone(),
+        )
+    }
+
+    pub fn num_spins(&self) -> usize {
+        self.lattice.num_sites
+    }
+
+    /// Effective field Σ_j J_ij s_j + h_i acting on `site`.
+    pub fn local_field(&self, site: usize) -> f64 {
+        self.lattice.neighbors[site]
+            .iter()
+            .map(|n| self.couplings[n.bond] * (n.sign * self.spins[n.site]) as f64)
+            .sum::<f64>()
+            + self.fields[site]
+    }
+
+    /// N random-site Metropolis flips.
+    pub fn metropolis_sweep(&mut self) {
+        let mut rng = rand::rng();
+        for _ in 0..self.num_spins() {
+            let site = rng.random_range(0..self.num_spins());
+            let delta_e = 2.0 * self.spins[site] as f64 * self.local_field(site);
+            if delta_e <= 0.0 || rng.random::<f64>() < (-delta_e / self.temperature).exp() {
+                self.spins[site] = -self.spins[site];
+            }
+        }
+    }
+
+    pub fn energy(&self) -> f64 {
+        let bonds: f64 = self
+            .lattice
+            .bonds
+            .iter()
+            .zip(&self.couplings)
+            .map(|(b, j)| j * (b.sign * self.spins[b.a] * self.spins[b.b]) as f64)
+            .sum();
+        let field: f64 = self
+            .spins
+            .iter()
+            .zip(&self.fields)
+            .map(|(&s, h)| h * s as f64)
+            .sum();
+        -bonds - field
+    }
+
+    pub fn magnetization(&self) -> f64 {
+        self.spins.iter().sum::<i32>() as f64 / self.num_spins() as f64
+    }
+
+    /// Overlap q = (1/N) Σ_i s_i^a s_i^b with another replica.
+    pub fn overlap(&self, other: &Self) -> f64 {
+        self.spins
+            .iter()
+            .zip(&other.spins)
+            .map(|(a, b)| (a * b) as f64)
+            .sum::<f64>()
+            / self.num_spins() as f64
+    }
+}
+
+impl Replica for DisorderedIsing {
+    fn energy(&self) -> f64 {
+        DisorderedIsing::energy(self)
+    }
+    fn temperature(&self) -> f64 {
+        self.temperature
+    }
+    fn set_temperature(&mut self, temperature: f64) {
+        self.temperature = temperature;
+    }
+    fn sweep(&mut self) {
+        self.metropolis_sweep();
+    }
+}
+
+/// Disorder-averaging driver
+/// - Draws `realisations` systems from `make_system`, each simulated as two replicas
+/// - After `thermalization` sweeps, records the overlap after each of `measurements`
+///   further sweeps, each advanced by `update` (e.g. `metropolis_sweep`)
+/// - Averages P(q), <q²> and <q⁴> over the disorder
+pub fn disorder_average<M, U>(
+    realisations: usize,
+    thermalization: usize,
+    measurements: usize,
+    bins: usize,
+    mut make_system: M,
+    mut update: U,
+) -> SpinGlassObservables
+where
+    M: FnMut() -> DisorderedIsing,
+    U: FnMut(&mut DisorderedIsing),
+{
+    assert!(realisations >= 2, "Need at least two disorder realisations");
+    assert!(measurements >= 1, "Need at least one measurement");
+    let mut histogram = vec![0.0; bins];
+    let mut q2_samples = Vec::with_capacity(realisations);
+    let mut q4_samples = Vec::with_capacity(realisations);
+    let mut num_spins = 0;
+    for _ in 0..realisations {
+        let mut a = make_system();
+        let mut b = a.replica();
+        num_spins = a.num_spins();
+        for _ in 0..thermalization {
+            update(&mut a);
+            update(&mut b);
+        }
+        let mut q2 = 0.0;
+        let mut q4 = 0.0;
+        for _ in 0..measurements {
+            update(&mut a);
+            update(&mut b);
+            let q = a.overlap(&b);
+            q2 += q * q;
+            q4 += q.powi(4);
+            let bin = (((q + 1.0) / 2.0 * bins as f64) as usize).min(bins - 1);
+            histogram[bin] += 1.0;
+        }
+        q2_samples.push(q2 / measurements as f64);
+        q4_samples.push(q4 / measurements as f64);
+    }
+
+    // Normalise to a probability density on [-1, 1].
+    let total: f64 = histogram.iter().sum();
+    let width = 2.0 / bins as f64;
+    let overlap_distribution = histogram.iter().map(|h| h / (total * width)).collect();
+
+    let n = num_spins as f64;
+    let blocks = realisations;
+    let series: [&[f64]; 2] = [&q2_samples, &q4_samples];
+    SpinGlassObservables {
+        realisations,
+        overlap_distribution,
+        q2: jackknife(&series, blocks, |x| x[0]),
+        q4: jackknife(&series, blocks, |x| x[1]),
+        susceptibility: jackknife(&series, blocks, |x| n * x[0]),
+        binder_ratio: jackknife(&series, blocks, |x| 0.5 * (3.0 - x[1] / (x[0] * x[0]))),
+    }
+}
+
+pub fn spin_glass_example() {
+    use crate::models::lattice::Boundary;
+    for t in [0.5, 1.0, 2.0] {
+        let obs = disorder_average(
+            20,
+            500,
+            500,
+            20,
+            || DisorderedIsing::edwards_anderson_pm_j(Lattice::square(6, Boundary::Periodic), t),
+            |s| s.metropolis_sweep(),
+        );
+        println!(
+            "EA ±J 6×6 at T = {}: χ_SG = {:.3} ± {:.3}, g = {:.3} ± {:.3}",
+            t,
+            obs.susceptibility.mean,
+            obs.susceptibility.error,
+            obs.binder_ratio.mean,
+            obs.binder_ratio.error
+        );
+    }
+}
+
+#[test]
+fn test_spin_glass_overlaps() {
+    use crate::models::lattice::Boundary;
+    // Paramagnet: the overlap is centred at 0 and the Binder ratio is small.
+    let hot = disorder_average(
+        8,
+        50,
+        400,
+        10,
+        || DisorderedIsing::edwards_anderson_gaussian(Lattice::square(6, Boundary::Periodic), 5.0),
+        |s| s.metropolis_sweep(),
+    );
+    assert!(hot.binder_ratio.mean < 0.5);
+    assert!(hot.susceptibility.mean < 5.0);
+    let density: f64 = hot.overlap_distribution.iter().sum::<f64>() * 0.2;
+    assert!((density - 1.0).abs() < 1e-9);
+
+    // A strong random field pins both replicas, so q ≈ 1 in every realisation.
+    let pinned = disorder_average(
+        4,
+        50,
+        100,
+        10,
+        || DisorderedIsing::random_field(Lattice::square(6, Boundary::Periodic), 0.5, 10.0),
+        |s| s.metropolis_sweep(),
+    );
+    assert!(pinned.q2.mean > 0.8);
+}
@@ -0,0 +1,627 @@
+use crate::diagnostics::{jackknife, jackknife_blocks, mean_with_error, Estimate};
+use crate::models::lattice::{Boundary, Lattice};
+use crate::physics::hilbert::{SpinBasis, SpinHamiltonian};
+use rand::Rng;
+
+/// Marks a missing leg in the linked vertex list.
+const NONE: usize = usize::MAX;
+
+/// One position of the SSE operator string.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+    Identity,
+    Diagonal(usize),
+    OffDiagonal(usize),
+}
+
+/// How loops leave a vertex they have entered
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LoopRule {
+    /// Deterministic operator loops (switch to the other site on the same side);
+    /// exact only at the Heisenberg point Δ = 1 without field.
+    OperatorLoop,
+    /// Directed loops with heat-bath exit probabilities ∝ the weight of the resulting
+    /// vertex, valid for any Δ and field at the price of some bounces.
+    DirectedLoop,
+}
+
+/// Spin-1/2 XXZ model H = J Σ_<ij> [Δ S^z_i S^z_j + ½(S^+_i S^-_j + S^-_i S^+_j)] - h Σ_i S^z_i
+/// by stochastic series expansion at finite temperature
+/// - Needs J > 0 on a bipartite lattice, where the off-diagonal signs cancel
+/// - Each bond operator is shifted by a constant C_b so that all diagonal vertex
+///   weights are non-negative; the field is shared out over the bonds of each site
+/// - `sweep` is one diagonal update of the operator string followed by a fixed number
+///   of loops, chosen from the running mean loop length to visit about 2M vertices
+/// - `spins` holds σ = 2S^z = ±1 of the state at imaginary time 0; the cutoff M
+///   (`operators.len()`) grows as needed
+pub struct StochasticSeries {
+    pub coupling: f64,
+    pub anisotropy: f64,
+    pub field: f64,
+    pub temperature: f64,
+    pub lattice: Lattice,
+    pub loop_rule: LoopRule,
+    pub spins: Vec<i8>,
+    pub operators: Vec<Operator>,
+    pub num_operators: usize,
+    /// Constant C_b per bond, refreshed at the start of every sweep.
+    constants: Vec<f64>,
+    /// Share of the field (on site a, on site b) carried by every bond.
+    bond_fields: Vec<(f64, f64)>,
+    mean_loop_length: f64,
+}
+
+/// Per-site measurements of one `StochasticSeries` run
+/// - `energy` = -<n> / (βN) + Σ_b C_b / N
+/// - `staggered_magnetization_squared` is <m_s²>, m_s = (1/N) Σ_i (±1)_i S^z_i,
+///   averaged over the propagated states of the operator string
+/// - `susceptibility` is the connected uniform χ = β (<M_z²> - <M_z>²) / N
+/// - `stiffness` is ρ_s = <n_a²> / (βN) averaged over the bond directions a, n_a the net
+///   number of spin hops along a, i.e. (1/N) ∂²F/∂φ² for a twist φ per bond along a
+pub struct StochasticSeriesObservables {
+    pub temperature: f64,
+    pub energy: Estimate,
+    pub staggered_magnetization_squared: Estimate,
+    pub susceptibility: Estimate,
+    pub stiffness: Estimate,
+    pub energy_series: Vec<f64>,
+}
+
+impl StochasticSeries {
+    /// Heisenberg antiferromagnet on the periodic L×L square lattice.
+    pub fn new(size: usize, coupling: f64, temperature: f64) -> Self {
+        Self::with_lattice(
+            Lattice::square(size, Boundary::Periodic),
+            coupling,
+            1.0,
+            0.0,
+            temperature,
+        )
+    }
+
+    /// Uses operator loops at the Heisenberg point and directed loops otherwise.
+    pub fn with_lattice(
+        lattice: Lattice,
+        coupling: f64,
+        anisotropy: f64,
+        field: f64,
+        temperature: f64,
+    ) -> Self {
+        assert!(coupling > 0.0, "SSE needs an antiferromagnetic J > 0");
+        assert!(lattice.is_bipartite(), "SSE needs a bipartite lattice");
+        assert!(
+            lattice.bonds.iter().all(|b| b.sign == 1),
+            "SSE does not support antiperiodic bonds"
+        );
+        let mut degree = vec![0; lattice.num_sites];
+        for bond in &lattice.bonds {
+            degree[bond.a] += 1;
+            degree[bond.b] += 1;
+        }
+        let bond_fields = lattice
+            .bonds
+            .iter()
+            .map(|b| (field / degree[b.a] as f64, field / degree[b.b] as f64))
+            .collect();
+        let mut rng = rand::rng();
+        let spins = (0..lattice.num_sites)
+            .map(|_| if rng.random_bool(0.5) { 1 } else { -1 })
+            .collect();
+        let loop_rule = if anisotropy == 1.0 && field == 0.0 {
+            LoopRule::OperatorLoop
+        } else {
+            LoopRule::DirectedLoop
+        };
+        Self {
+            coupling,
+            anisotropy,
+            field,
+            temperature,
+            constants: vec![0.0; lattice.bonds.len()],
+            lattice,
+            loop_rule,
+            spins,
+            operators: vec![Operator::Identity; 20],
+            num_operators: 0,
+            bond_fields,
+            mean_loop_length: 0.0,
+        }
+    }
+
+    pub fn num_spins(&self) -> usize {
+        self.lattice.num_sites
+    }
+
+    /// Diagonal energy of bond b for σ_a, σ_b = ±1, without the constant.
+    fn diagonal_energy(&self, bond: usize, sa: i8, sb: i8) -> f64 {
+        let (ha, hb) = self.bond_fields[bond];
+        0.25 * self.coupling * self.anisotropy * (sa * sb) as f64
+            - 0.5 * (ha * sa as f64 + hb * sb as f64)
+    }
+
+    /// C_b = the largest diagonal energy, plus J/4 for directed loops so that no
+    /// diagonal vertex is forbidden (operator loops rely on exactly that).
+    fn refresh_constants(&mut self) {
+        assert!(
+            self.loop_rule == LoopRule::DirectedLoop
+                || (self.anisotropy == 1.0 && self.field == 0.0),
+            "Operator loops need Δ = 1 and no field"
+        );
+        let epsilon = match self.loop_rule {
+            LoopRule::OperatorLoop => 0.0,
+            LoopRule::DirectedLoop => 0.25 * self.coupling,
+        };
+        for b in 0..self.constants.len() {
+            let highest = [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+                .iter()
+                .map(|&(sa, sb)| self.diagonal_energy(b, sa, sb))
+                .fold(f64::NEG_INFINITY, f64::max);
+            self.constants[b] = highest + epsilon;
+        }
+    }
+
+    /// Matrix element of C_b - H_b between the states below and above a vertex,
+    /// `legs` = [σ_a, σ_b] below followed by [σ_a, σ_b] above.
+    fn vertex_weight(&self, bond: usize, legs: [i8; 4]) -> f64 {
+        if legs[0] == legs[2] && legs[1] == legs[3] {
+            self.constants[bond] - self.diagonal_energy(bond, legs[0], legs[1])
+        } else if legs[0] == -legs[2] && legs[1] == -legs[3] && legs[0] == -legs[1] {
+            0.5 * self.coupling
+        } else {
+            0.0
+        }
+    }
+
+    pub fn sweep(&mut self) {
+        self.refresh_constants();
+        self.diagonal_update();
+        self.loop_update();
+    }
+
+    /// Inserts and removes diagonal operators position by position, with acceptance
+    /// β N_b W / (M - n) for insertion on a random bond and its inverse for removal.
+    fn diagonal_update(&mut self) {
+        let mut rng = rand::rng();
+        let beta = 1.0 / self.temperature;
+        let num_bonds = self.lattice.bonds.len();
+        let cutoff = self.operators.len();
+        let mut state = self.spins.clone();
+        for p in 0..cutoff {
+            match self.operators[p] {
+                Operator::Identity => {
+                    let b = rng.random_range(0..num_bonds);
+                    let (a, c) = (self.lattice.bonds[b].a, self.lattice.bonds[b].b);
+                    let weight = self.vertex_weight(b, [state[a], state[c], state[a], state[c]]);
+                    let free = (cutoff - self.num_operators) as f64;
+                    if rng.random::<f64>() * free < beta * num_bonds as f64 * weight {
+                        self.operators[p] = Operator::Diagonal(b);
+                        self.num_operators += 1;
+                    }
+                }
+                Operator::Diagonal(b) => {
+                    let (a, c) = (self.lattice.bonds[b].a, self.lattice.bonds[b].b);
+                    let weight = self.vertex_weight(b, [state[a], state[c], state[a], state[c]]);
+                    let free = (cutoff - self.num_operators + 1) as f64;
+                    if rng.random::<f64>() * beta * num_bonds as f64 * weight < free {
+                        self.operators[p] = Operator::Identity;
+                        self.num_operators -= 1;
+                    }
+                }
+                Operator::OffDiagonal(b) => {
+                    let (a, c) = (self.lattice.bonds[b].a, self.lattice.bonds[b].b);
+                    state[a] = -state[a];
+                    state[c] = -state[c];
+                }
+            }
+        }
+        // Keep M comfortably above n; the expansion is exact for any M > n.
+        let wanted = self.num_operators + self.num_operators / 3;
+        if wanted > cutoff {
+            self.operators.resize(wanted, Operator::Identity);
+        }
+    }
+
+    /// Builds the linked vertex list, flips loops of legs and maps the result back
+    /// onto the operator string and the state; sites without operators flip freely.
+    fn loop_update(&mut self) {
+        let mut rng = rand::rng();
+        let n = self.num_spins();
+        let cutoff = self.operators.len();
+        let mut first = vec![NONE; n];
+        let mut last = vec![NONE; n];
+        let mut link = vec![NONE; 4 * cutoff];
+        let mut legs = vec![0i8; 4 * cutoff];
+        let mut state = self.spins.clone();
+        for (p, op) in self.operators.iter().enumerate() {
+            let b = match *op {
+                Operator::Identity => continue,
+                Operator::Diagonal(b) | Operator::OffDiagonal(b) => b,
+            };
+            let (a, c) = (self.lattice.bonds[b].a, self.lattice.bonds[b].b);
+            legs[4 * p] = state[a];
+            legs[4 * p + 1] = state[c];
+            if let Operator::OffDiagonal(_) = op {
+                state[a] = -state[a];
+                state[c] = -state[c];
+            }
+            legs[4 * p + 2] = state[a];
+            legs[4 * p + 3] = state[c];
+            for (leg, site) in [(0, a), (1, c)] {
+                let v = 4 * p + leg;
+                if last[site] == NONE {
+                    first[site] = v;
+                } else {
+                    link[v] = last[site];
+                    link[last[site]] = v;
+                }
+                last[site] = v + 2;
+            }
+        }
+        for site in 0..n {
+            if first[site] != NONE {
+                link[first[site]] = last[site];
+                link[last[site]] = first[site];
+            }
+        }
+
+        if self.num_operators > 0 {
+            let loops = if self.mean_loop_length == 0.0 {
+                1
+            } else {
+                ((2.0 * cutoff as f64 / self.mean_loop_length).round() as usize).max(1)
+            };
+            for _ in 0..loops {
+                let p = loop {
+                    let p = rng.random_range(0..cutoff);
+                    if self.operators[p] != Operator::Identity {
+                        break p;
+                    }
+                };
+                let length = self.flip_loop(4 * p + rng.random_range(0..4), &link, &mut legs);
+                if self.mean_loop_length == 0.0 {
+                    self.mean_loop_length = length as f64;
+                } else {
+                    self.mean_loop_length += 0.01 * (length as f64 - self.mean_loop_length);
+                }
+            }
+        }
+
+        for (p, op) in self.operators.iter_mut().enumerate() {
+            if let Operator::Diagonal(b) | Operator::OffDiagonal(b) = *op {
+                let v = &legs[4 * p..4 * p + 4];
+                *op = if v[0] == v[2] && v[1] == v[3] {
+                    Operator::Diagonal(b)
+                } else {
+                    Operator::OffDiagonal(b)
+                };
+            }
+        }
+        for site in 0..n {
+            if first[site] != NONE {
+                self.spins[site] = legs[first[site]];
+            } else if rng.random_bool(0.5) {
+                self.spins[site] = -self.spins[site];
+            }
+        }
+    }
+
+    /// Moves a loop head from the entrance leg `start` until it closes, flipping the
+    /// legs it passes; returns the number of vertices visited.
+    fn flip_loop(&self, start: usize, link: &[usize], legs: &mut [i8]) -> usize {
+        let mut rng = rand::rng();
+        let mut v = start;
+        let mut length = 0;
+        loop {
+            length += 1;
+            let p = v / 4;
+            let exit = match self.loop_rule {
+                LoopRule::OperatorLoop => v ^ 1,
+                LoopRule::DirectedLoop => {
+                    let b = match self.operators[p] {
+                        Operator::Diagonal(b) | Operator::OffDiagonal(b) => b,
+                        Operator::Identity => unreachable!("Loop entered an identity"),
+                    };
+                    let entrance = v % 4;
+                    // Weight of the vertex after flipping the entrance and exit legs;
+                    // the bounce (exit = entrance) leaves the vertex unchanged.
+                    let weights: Vec<f64> = (0..4)
+                        .map(|exit| {
+                            let mut trial = [
+                                legs[4 * p],
+                                legs[4 * p + 1],
+                                legs[4 * p + 2],
+                                legs[4 * p + 3],
+                            ];
+                            if exit != entrance {
+                                trial[entrance] = -trial[entrance];
+                                trial[exit] = -trial[exit];
+                            }
+                            self.vertex_weight(b, trial)
+                        })
+                        .collect();
+                    let mut r = rng.random::<f64>() * weights.iter().sum::<f64>();
+                    let mut exit = 0;
+                    while exit < 3 && r >= weights[exit] {
+                        r -= weights[exit];
+                        exit += 1;
+                    }
+                    4 * p + exit
+                }
+            };
+            if exit != v {
+                legs[v] = -legs[v];
+                legs[exit] = -legs[exit];
+            }
+            if exit == start {
+                break;
+            }
+            v = link[exit];
+            if v == start {
+                break;
+            }
+        }
+        length
+    }
+
+    /// (<m_s²> over the propagated states, net spin hops per bond direction) of
+    /// the current operator string.
+    fn propagate(&self) -> (f64, Vec<i64>) {
+        let n = self.num_spins();
+        let phase = |i: usize| {
+            if self.lattice.sublattice(i) == 0 {
+                1
+            } else {
+                -1
+            }
+        };
+        let mut state = self.spins.clone();
+        // Twice the staggered S^z sum, kept as an integer.
+        let mut staggered: i64 = (0..n).map(|i| phase(i) * state[i] as i64).sum();
+        let mut windings = vec![0; self.lattice.num_directions()];
+        let mut sum = 0.0;
+        let mut count = 0;
+        for op in &self.operators {
+            match *op {
+                Operator::Identity => continue,
+                Operator::OffDiagonal(b) => {
+                    let bond = &self.lattice.bonds[b];
+                    windings[bond.direction] += state[bond.a] as i64;
+                    for site in [bond.a, bond.b] {
+                        staggered -= 2 * phase(site) * state[site] as i64;
+                        state[site] = -state[site];
+                    }
+                }
+                Operator::Diagonal(_) => {}
+            }
+            let m = staggered as f64 / (2 * n) as f64;
+            sum += m * m;
+            count += 1;
+        }
+        if count == 0 {
+            let m = staggered as f64 / (2 * n) as f64;
+            return (m * m, windings);
+        }
+        (sum / count as f64, windings)
+    }
+
+    /// Runs `thermalization` updates, then measures after each of `measurements`
+    /// further updates.
+    pub fn measure_with<F>(
+        &mut self,
+        thermalization: usize,
+        measurements: usize,
+        mut update: F,
+    ) -> StochasticSeriesObservables
+    where
+        F: FnMut(&mut Self),
+    {
+        for _ in 0..thermalization {
+            update(self);
+        }
+        let n = self.num_spins() as f64;
+        let beta = 1.0 / self.temperature;
+        let mut energies = Vec::with_capacity(measurements);
+        let mut staggered = Vec::with_capacity(measurements);
+        let mut mz_series = Vec::with_capacity(measurements);
+        let mut mz2_series = Vec::with_capacity(measurements);
+        let mut stiffnesses = Vec::with_capacity(measurements);
+        for _ in 0..measurements {
+            update(self);
+            let shift: f64 = self.constants.iter().sum();
+            energies.push((shift - self.num_operators as f64 / beta) / n);
+            let mz = self.spins.iter().map(|&s| s as f64).sum::<f64>() / 2.0;
+            mz_series.push(mz);
+            mz2_series.push(mz * mz);
+            let (ms2, windings) = self.propagate();
+            staggered.push(ms2);
+            let w2 = windings.iter().map(|&w| (w * w) as f64).sum::<f64>();
+            stiffnesses.push(w2 / (windings.len() as f64 * beta * n));
+        }
+        let blocks = jackknife_blocks(&[&mz_series, &mz2_series]);
+        StochasticSeriesObservables {
+            temperature: self.temperature,
+            energy: mean_with_error(&energies),
+            staggered_magnetization_squared: mean_with_error(&staggered),
+            susceptibility: jackknife(&[&mz_series, &mz2_series], blocks, |x| {
+                beta * (x[1] - x[0] * x[0]) / n
+            }),
+            stiffness: mean_with_error(&stiffnesses),
+            energy_series: energies,
+        }
+    }
+
+    /// The same observables from full diagonalisation in every S^z sector, with zero
+    /// errors; the stiffness follows from F(φ) with twisted bonds. Small lattices only.
+    pub fn exact_thermodynamics(&self) -> StochasticSeriesObservables {
+        let n = self.num_spins();
+        let beta = 1.0 / self.temperature;
+        let hamiltonian = SpinHamiltonian {
+            field: self.field,
+            ..SpinHamiltonian::xxz(
+                &self.lattice,
+                self.coupling * self.anisotropy,
+                self.coupling,
+            )
+        };
+        let staggered = |state: u32| {
+            let sum: f64 = (0..n)
+                .map(|i| {
+                    let s = if state >> i & 1 == 1 { 0.5 } else { -0.5 };
+                    if self.lattice.sublattice(i) == 0 {
+                        s
+                    } else {
+                        -s
+                    }
+                })
+                .sum();
+            (sum / n as f64).powi(2)
+        };
+        // (E, M_z, <m_s²>) of every eigenstate.
+        let mut levels = Vec::new();
+        for up in 0..=n {
+            let basis = SpinBasis::new(n, Some(up), None);
+            let ms2 = basis.diagonal_operator(staggered);
+            for pair in hamiltonian.operator(&basis).full_spectrum() {
+                let mz = up as f64 - n as f64 / 2.0;
+                levels.push((pair.value, mz, ms2.expectation(&pair.vector)));
+            }
+        }
+        let e0 = levels.iter().map(|l| l.0).fold(f64::INFINITY, f64::min);
+        let weights: Vec<f64> = levels.iter().map(|l| (-beta * (l.0 - e0)).exp()).collect();
+        let z: f64 = weights.iter().sum();
+        let average = |f: &dyn Fn(&(f64, f64, f64)) -> f64| {
+            levels
+                .iter()
+                .zip(&weights)
+                .map(|(l, w)| w * f(l))
+                .sum::<f64>()
+                / z
+        };
+
+        let free_energy = |phi: f64, direction: usize| {
+            let twisted = SpinHamiltonian {
+                bond_phases: self
+                    .lattice
+                    .bonds
+                    .iter()
+                    .map(|b| if b.direction == direction { phi } else { 0.0 })
+                    .collect(),
+                ..hamiltonian.clone()
+            };
+            let sum: f64 = (0..=n)
+                .flat_map(|up| {
+                    twisted
+                        .operator(&SpinBasis::new(n, Some(up), None))
+                        .eigenvalues()
+                })
+                .map(|e| (-beta * (e - e0)).exp())
+                .sum();
+            e0 - sum.ln() / beta
+        };
+        let phi = 1e-2;
+        let directions = self.lattice.num_directions();
+        let stiffness = (0..directions)
+            .map(|d| {
+                (free_energy(phi, d) + free_energy(-phi, d) - 2.0 * free_energy(0.0, d))
+                    / (phi * phi * n as f64)
+            })
+            .sum::<f64>()
+            / directions as f64;
+
+        let exact = |mean| Estimate { mean, error: 0.0 };
+        StochasticSeriesObservables {
+            temperature: self.temperature,
+            energy: exact(average(&|l| l.0) / n as f64),
+            staggered_magnetization_squared: exact(average(&|l| l.2)),
+            susceptibility: exact(
+                beta * (average(&|l| l.1 * l.1) - average(&|l| l.1).powi(2)) / n as f64,
+            ),
+            stiffness: exact(stiffness),
+            energy_series: Vec::new(),
+        }
+    }
+}
+
+pub fn stochastic_series_example() {
+    // Square-lattice Heisenberg antiferromagnet: e_0 ≈ -0.6694 J, ρ_s(T = 0) ≈ 0.18 J.
+    for t in [1.0, 0.5, 0.25] {
+        let mut sse = StochasticSeries::new(8, 1.0, t);
+        let obs = sse.measure_with(1000, 5000, |s| s.sweep());
+        println!(
+            "SSE 8×8 T = {:.2}: e = {:.5} ± {:.5}, <m_s²> = {:.4} ± {:.4}, χ = {:.4} ± {:.4}, ρ_s = {:.4} ± {:.4}",
+            t,
+            obs.energy.mean,
+            obs.energy.error,
+            obs.staggered_magnetization_squared.mean,
+            obs.staggered_magnetization_squared.error,
+            obs.susceptibility.mean,
+            obs.susceptibility.error,
+            obs.stiffness.mean,
+            obs.stiffness.error
+        );
+    }
+}
+
+#[test]
+fn test_against_exact_diagonalization() {
+    let compare = |sse: &mut StochasticSeries| {
+        let exact = sse.exact_thermodynamics();
+        let obs = sse.measure_with(1000, 20_000, |s| s.sweep());
+        for (name, a, b) in [
+            ("energy", obs.energy, exact.energy),
+            (
+                "m_s²",
+                obs.staggered_magnetization_squared,
+                exact.staggered_magnetization_squared,
+            ),
+            ("χ", obs.susceptibility, exact.susceptibility),
+            ("ρ_s", obs.stiffness, exact.stiffness),
+        ] {
+            assert!(
+                (a.mean - b.mean).abs() < 5.0 * a.error + 2e-3,
+                "{}: {} ± {} vs exact {}",
+                name,
+                a.mean,
+                a.error,
+                b.mean
+            );
+        }
+    };
+    // Heisenberg ring with operator loops.
+    let mut heisenberg = StochasticSeries::with_lattice(
+        Lattice::hypercubic(1, 8, Boundary::Periodic),
+        1.0,
+        1.0,
+        0.0,
+        0.5,
+    );
+    assert_eq!(heisenberg.loop_rule, LoopRule::OperatorLoop);
+    compare(&mut heisenberg);
+
+    // Easy-plane XXZ in a field with directed loops, on the 2×2 torus (doubled bonds).
+    let torus = Lattice::square(2, Boundary::Periodic);
+    let mut xxz = StochasticSeries::with_lattice(torus, 1.0, 0.5, 0.4, 0.4);
+    assert_eq!(xxz.loop_rule, LoopRule::DirectedLoop);
+    compare(&mut xxz);
+}
+
+#[test]
+fn test_directed_loops_at_heisenberg_point() {
+    // Both loop rules sample the same ensemble on an open chain, where ρ_s vanishes.
+    let measure = |rule| {
+        let mut sse = StochasticSeries::with_lattice(
+            Lattice::hypercubic(1, 6, Boundary::Open),
+            1.0,
+            1.0,
+            0.0,
+            0.3,
+        );
+        sse.loop_rule = rule;
+        sse.measure_with(1000, 10_000, |s| s.sweep())
+    };
+    let operator = measure(LoopRule::OperatorLoop);
+    let directed = measure(LoopRule::DirectedLoop);
+    let sigma = (operator.energy.error.powi(2) + directed.energy.error.powi(2)).sqrt();
+    assert!((operator.energy.mean - directed.energy.mean).abs() < 5.0 * sigma + 1e-3);
+    assert!(operator.stiffness.mean == 0.0 && directed.stiffness.mean == 0.0);
+}
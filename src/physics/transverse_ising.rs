@@ -0,0 +1,393 @@
+use crate::diagnostics::{jackknife, jackknife_blocks, mean_with_error, Estimate};
+use crate::models::lattice::{Boundary, Lattice};
+use crate::physics::correlations::{fft, Complex};
+use crate::physics::ising_model::cluster_sweep;
+use rand::Rng;
+
+/// Transverse-field Ising model H = -J Σ_<ij> σᶻ_i σᶻ_j - Γ Σ_i σˣ_i by Suzuki–Trotter QMC
+/// - Maps to a classical Ising model on `lattice` × M imaginary-time slices with
+///   Δτ = β / M, spatial coupling Δτ J and temporal coupling K = -½ ln tanh(Δτ Γ)
+/// - `spins[τ N + i]` holds spin i on slice τ; imaginary time is periodic
+/// - Sampled with Wolff clusters grown through both the spatial and temporal bonds
+/// - Observables carry a Trotter error O(Δτ²); see `trotter_extrapolation`
+pub struct TransverseIsing {
+    pub coupling: f64,
+    pub transverse_field: f64,
+    pub temperature: f64,
+    pub trotter_slices: usize,
+    pub lattice: Lattice,
+    pub spins: Vec<i32>,
+    /// Running mean of the Wolff cluster size, which sets the moves per sweep.
+    mean_cluster_size: f64,
+}
+
+/// Per-site measurements of one `TransverseIsing` run at fixed Trotter number
+/// - `energy` is the thermodynamic estimator -∂ ln Z / ∂β per site
+/// - `time_correlation[k]` is <σᶻ_i(0) σᶻ_i(k Δτ)>, k = 0..=M / 2
+pub struct TransverseIsingObservables {
+    pub temperature: f64,
+    pub trotter_slices: usize,
+    pub energy: Estimate,
+    pub transverse_magnetization: Estimate,
+    pub abs_magnetization: Estimate,
+    pub magnetization_squared: Estimate,
+    pub binder_cumulant: Estimate,
+    pub time_correlation: Vec<Estimate>,
+    pub energy_series: Vec<f64>,
+}
+
+impl TransverseIsing {
+    /// Square L×L lattice with periodic boundaries.
+    pub fn new(
+        size: usize,
+        coupling: f64,
+        transverse_field: f64,
+        temperature: f64,
+        trotter_slices: usize,
+    ) -> Self {
+        Self::with_lattice(
+            Lattice::square(size, Boundary::Periodic),
+            coupling,
+            transverse_field,
+            temperature,
+            trotter_slices,
+        )
+    }
+
+    /// Periodic chain of `length` spins.
+    pub fn chain(
+        length: usize,
+        coupling: f64,
+        transverse_field: f64,
+        temperature: f64,
+        trotter_slices: usize,
+    ) -> Self {
+        Self::with_lattice(
+            Lattice::hypercubic(1, length, Boundary::Periodic),
+            coupling,
+            transverse_field,
+            temperature,
+            trotter_slices,
+        )
+    }
+
+    pub fn with_lattice(
+        lattice: Lattice,
+        coupling: f64,
+        transverse_field: f64,
+        temperature: f64,
+        trotter_slices: usize,
+    ) -> Self {
+        assert!(trotter_slices >= 2, "Need at least two Trotter slices");
+        assert!(
+            coupling > 0.0 && transverse_field > 0.0,
+            "Cluster updates need J > 0 and Γ > 0"
+        );
+        let mut rng = rand::rng();
+        let spins = (0..lattice.num_sites * trotter_slices)
+            .map(|_| if rng.random_bool(0.5) { 1 } else { -1 })
+            .collect();
+        Self {
+            coupling,
+            transverse_field,
+            temperature,
+            trotter_slices,
+            lattice,
+            spins,
+            mean_cluster_size: 0.0,
+        }
+    }
+
+    pub fn num_spins(&self) -> usize {
+        self.lattice.num_sites
+    }
+
+    pub fn delta_tau(&self) -> f64 {
+        1.0 / (self.temperature * self.trotter_slices as f64)
+    }
+
+    /// K = -½ ln tanh(Δτ Γ), the coupling between neighbouring slices.
+    pub fn temporal_coupling(&self) -> f64 {
+        -0.5 * (self.delta_tau() * self.transverse_field).tanh().ln()
+    }
+
+    /// Wolff update on the (d+1)-dimensional lattice
+    /// - Aligned spatial neighbours join with probability 1 - exp(-2 Δτ J), aligned
+    ///   neighbours in imaginary time with 1 - exp(-2K)
+    /// - Returns the cluster size
+    pub fn wolff_step(&mut self) -> usize {
+        let mut rng = rand::rng();
+        let n = self.num_spins();
+        let m = self.trotter_slices;
+        let p_space = 1.0 - (-2.0 * self.delta_tau() * self.coupling).exp();
+        let p_time = 1.0 - (-2.0 * self.temporal_coupling()).exp();
+        let seed = rng.random_range(0..n * m);
+        let mut in_cluster = vec![false; n * m];
+        in_cluster[seed] = true;
+        let mut stack = vec![seed];
+        let mut cluster_size = 1;
+        while let Some(index) = stack.pop() {
+            let (tau, site) = (index / n, index % n);
+            let s = self.spins[index];
+            let spatial = self.lattice.neighbors[site]
+                .iter()
+                .map(|nb| (tau * n + nb.site, nb.sign, p_space));
+            let temporal = [(tau + 1) % m, (tau + m - 1) % m]
+                .into_iter()
+                .map(|t| (t * n + site, 1, p_time));
+            for (other, sign, p) in spatial.chain(temporal) {
+                if !in_cluster[other] && sign * self.spins[other] == s && rng.random::<f64>() < p {
+                    in_cluster[other] = true;
+                    stack.push(other);
+                    cluster_size += 1;
+                }
+            }
+        }
+        for (spin, &flip) in self.spins.iter_mut().zip(&in_cluster) {
+            if flip {
+                *spin = -*spin;
+            }
+        }
+        cluster_size
+    }
+
+    /// About N M / <cluster size> Wolff moves, returning the number of moves;
+    /// see `cluster_sweep`.
+    pub fn wolff_sweep(&mut self) -> usize {
+        let mut mean = self.mean_cluster_size;
+        let moves = cluster_sweep(self.spins.len(), &mut mean, || self.wolff_step());
+        self.mean_cluster_size = mean;
+        moves
+    }
+
+    /// <σˣ> per site: the mean over temporal bonds of tanh(Δτ Γ) for parallel
+    /// and coth(Δτ Γ) for antiparallel neighbours.
+    pub fn transverse_magnetization(&self) -> f64 {
+        let n = self.num_spins();
+        let m = self.trotter_slices;
+        let t = (self.delta_tau() * self.transverse_field).tanh();
+        let mut sum = 0.0;
+        for tau in 0..m {
+            let next = (tau + 1) % m;
+            for i in 0..n {
+                let parallel = self.spins[tau * n + i] == self.spins[next * n + i];
+                sum += if parallel { t } else { 1.0 / t };
+            }
+        }
+        sum / (n * m) as f64
+    }
+
+    /// Energy estimator per site, -(J / NM) Σ_τ Σ_<ij> s s - Γ <σˣ>.
+    pub fn energy_per_spin(&self) -> f64 {
+        let n = self.num_spins();
+        let bonds: i32 = (0..self.trotter_slices)
+            .map(|tau| {
+                let slice = &self.spins[tau * n..(tau + 1) * n];
+                self.lattice
+                    .bonds
+                    .iter()
+                    .map(|b| b.sign * slice[b.a] * slice[b.b])
+                    .sum::<i32>()
+            })
+            .sum();
+        let classical = -self.coupling * bonds as f64 / (n * self.trotter_slices) as f64;
+        classical - self.transverse_field * self.transverse_magnetization()
+    }
+
+    /// Longitudinal magnetisation per site, averaged over the slices.
+    pub fn magnetization(&self) -> f64 {
+        self.spins.iter().sum::<i32>() as f64 / self.spins.len() as f64
+    }
+
+    /// Imaginary-time autocorrelation <s_i(τ) s_i(τ + kΔτ)> of the current configuration,
+    /// averaged over sites and origins, for k = 0..=M / 2 (via FFT along each worldline).
+    pub fn time_correlation(&self) -> Vec<f64> {
+        let n = self.num_spins();
+        let m = self.trotter_slices;
+        let mut power = vec![Complex::default(); m];
+        let mut line = vec![Complex::default(); m];
+        for i in 0..n {
+            for (tau, x) in line.iter_mut().enumerate() {
+                *x = Complex::new(self.spins[tau * n + i] as f64, 0.0);
+            }
+            fft(&mut line, false);
+            for (p, x) in power.iter_mut().zip(&line) {
+                p.re += x.norm_sqr();
+            }
+        }
+        fft(&mut power, true);
+        let norm = (n * m * m) as f64;
+        power[..=m / 2].iter().map(|p| p.re / norm).collect()
+    }
+
+    /// Runs `thermalization` updates, then measures after each of `measurements`
+    /// further updates.
+    pub fn measure_with<F>(
+        &mut self,
+        thermalization: usize,
+        measurements: usize,
+        mut update: F,
+    ) -> TransverseIsingObservables
+    where
+        F: FnMut(&mut Self),
+    {
+        for _ in 0..thermalization {
+            update(self);
+        }
+        let mut energies = Vec::with_capacity(measurements);
+        let mut transverse = Vec::with_capacity(measurements);
+        let mut magnetizations = Vec::with_capacity(measurements);
+        let mut correlations = vec![Vec::with_capacity(measurements); self.trotter_slices / 2 + 1];
+        for _ in 0..measurements {
+            update(self);
+            energies.push(self.energy_per_spin());
+            transverse.push(self.transverse_magnetization());
+            magnetizations.push(self.magnetization());
+            for (series, c) in correlations.iter_mut().zip(self.time_correlation()) {
+                series.push(c);
+            }
+        }
+
+        let abs_m: Vec<f64> = magnetizations.iter().map(|m| m.abs()).collect();
+        let m2: Vec<f64> = magnetizations.iter().map(|m| m * m).collect();
+        let m4: Vec<f64> = m2.iter().map(|m| m * m).collect();
+        let blocks = jackknife_blocks(&[&energies, &m2]);
+        TransverseIsingObservables {
+            temperature: self.temperature,
+            trotter_slices: self.trotter_slices,
+            energy: mean_with_error(&energies),
+            transverse_magnetization: mean_with_error(&transverse),
+            abs_magnetization: mean_with_error(&abs_m),
+            magnetization_squared: mean_with_error(&m2),
+            binder_cumulant: jackknife(&[&m2, &m4], blocks, |x| 1.0 - x[1] / (3.0 * x[0] * x[0])),
+            time_correlation: correlations.iter().map(|s| mean_with_error(s)).collect(),
+            energy_series: energies,
+        }
+    }
+
+    /// Independent runs with Wolff sweeps at each Trotter number in `slices`, all at the
+    /// current temperature and couplings; feed the results to `trotter_extrapolation`.
+    pub fn trotter_scan(
+        &self,
+        slices: &[usize],
+        thermalization: usize,
+        measurements: usize,
+    ) -> Vec<TransverseIsingObservables> {
+        slices
+            .iter()
+            .map(|&m| {
+                let mut model = Self::with_lattice(
+                    self.lattice.clone(),
+                    self.coupling,
+                    self.transverse_field,
+                    self.temperature,
+                    m,
+                );
+                model.measure_with(thermalization, measurements, |q| {
+                    q.wolff_sweep();
+                })
+            })
+            .collect()
+    }
+}
+
+/// Weighted least-squares fit of A(M) = A_∞ + c / M² to `(M, A(M))` points;
+/// returns A_∞ with its standard error.
+pub fn trotter_extrapolation(points: &[(usize, Estimate)]) -> Estimate {
+    assert!(points.len() >= 2, "Need at least two Trotter numbers");
+    let (mut s, mut sx, mut sy, mut sxx, mut sxy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for &(m, a) in points {
+        let x = 1.0 / (m * m) as f64;
+        // Points without an error bar get unit weight.
+        let w = if a.error > 0.0 {
+            1.0 / (a.error * a.error)
+        } else {
+            1.0
+        };
+        s += w;
+        sx += w * x;
+        sy += w * a.mean;
+        sxx += w * x * x;
+        sxy += w * x * a.mean;
+    }
+    let det = s * sxx - sx * sx;
+    Estimate {
+        mean: (sxx * sy - sx * sxy) / det,
+        error: (sxx / det).sqrt(),
+    }
+}
+
+pub fn transverse_ising_example() {
+    // Critical chain (Γ = J): the ground-state energy per site is -4/π ≈ -1.2732.
+    let model = TransverseIsing::chain(32, 1.0, 1.0, 0.05, 8);
+    let runs = model.trotter_scan(&[64, 96, 128], 200, 1000);
+    let points: Vec<(usize, Estimate)> =
+        runs.iter().map(|r| (r.trotter_slices, r.energy)).collect();
+    for (m, e) in &points {
+        println!("M = {:4}: e = {:.5} ± {:.5}", m, e.mean, e.error);
+    }
+    let e = trotter_extrapolation(&points);
+    println!(
+        "Trotter-extrapolated e = {:.5} ± {:.5} (exact -4/π = {:.5})",
+        e.mean,
+        e.error,
+        -4.0 / std::f64::consts::PI
+    );
+
+    // Square lattice on both sides of the quantum critical field Γ_c ≈ 3.04 J.
+    for gamma in [2.0, 4.0] {
+        let mut square = TransverseIsing::new(8, 1.0, gamma, 0.25, 32);
+        let obs = square.measure_with(100, 500, |q| {
+            q.wolff_sweep();
+        });
+        println!(
+            "Square Γ = {}: <m²> = {:.4} ± {:.4}, <σˣ> = {:.4} ± {:.4}",
+            gamma,
+            obs.magnetization_squared.mean,
+            obs.magnetization_squared.error,
+            obs.transverse_magnetization.mean,
+            obs.transverse_magnetization.error
+        );
+    }
+}
+
+#[test]
+fn test_two_site_energy_extrapolates_to_exact() {
+    // Open pair: levels ±sqrt(J² + 4Γ²), -J and +J.
+    let (j, gamma, t): (f64, f64, f64) = (1.0, 0.8, 0.5);
+    let r = (j * j + 4.0 * gamma * gamma).sqrt();
+    let levels = [-r, r, -j, j];
+    let z: f64 = levels.iter().map(|e| (-e / t).exp()).sum();
+    let exact = levels.iter().map(|e| e * (-e / t).exp()).sum::<f64>() / z / 2.0;
+
+    let model =
+        TransverseIsing::with_lattice(Lattice::hypercubic(1, 2, Boundary::Open), j, gamma, t, 4);
+    let runs = model.trotter_scan(&[8, 16, 32], 200, 20_000);
+    let points: Vec<(usize, Estimate)> =
+        runs.iter().map(|r| (r.trotter_slices, r.energy)).collect();
+    let e = trotter_extrapolation(&points);
+    assert!(
+        (e.mean - exact).abs() < 5.0 * e.error + 2e-3,
+        "extrapolated {} ± {} vs exact {}",
+        e.mean,
+        e.error,
+        exact
+    );
+    let c = &runs[2].time_correlation;
+    assert!((c[0].mean - 1.0).abs() < 1e-12);
+    assert!(c[1].mean < 1.0 && c[8].mean > 0.0);
+}
+
+#[test]
+fn test_transverse_field_destroys_order() {
+    let measure = |gamma: f64| {
+        TransverseIsing::chain(16, 1.0, gamma, 0.1, 32).measure_with(100, 300, |q| {
+            q.wolff_sweep();
+        })
+    };
+    let ordered = measure(0.3);
+    let disordered = measure(3.0);
+    assert!(ordered.magnetization_squared.mean > 0.8);
+    assert!(disordered.magnetization_squared.mean < 0.2);
+    assert!(disordered.transverse_magnetization.mean > ordered.transverse_magnetization.mean);
+}
@@ -0,0 +1,378 @@
+use crate::diagnostics::{jackknife, jackknife_blocks, mean_with_error, Estimate};
+use crate::models::lattice::{Boundary, Lattice, LatticeKind};
+use rand::Rng;
+use std::f64::consts::PI;
+/// O(n) vector spin model, H = -J Σ_<ij> S_i · S_j with unit vectors S_i ∈ R^N
+/// - `XyModel` (N = 2) and `HeisenbergModel` (N = 3) on any shared `Lattice`
+/// - Metropolis small-angle moves, microcanonical over-relaxation and
+///   embedded-cluster Wolff updates
+/// - Helicity modulus and vortex density for the XY model (BKT transition)
+pub struct VectorSpinModel<const N: usize> {
+    pub coupling: f64,
+    pub temperature: f64,
+    /// Largest displacement of a Metropolis proposal before renormalisation.
+    pub step_size: f64,
+    pub lattice: Lattice,
+    pub spins: Vec<[f64; N]>,
+}
+
+pub type XyModel = VectorSpinModel<2>;
+pub type HeisenbergModel = VectorSpinModel<3>;
+
+/// Measurement phase of a `VectorSpinModel`; the XY-only quantities are `None` for N ≠ 2.
+pub struct VectorSpinObservables {
+    pub energy: Estimate,
+    pub abs_magnetization: Estimate,
+    pub susceptibility: Estimate,
+    pub helicity_modulus: Option<Estimate>,
+    pub vortex_density: Option<Estimate>,
+}
+
+fn dot<const N: usize>(a: &[f64; N], b: &[f64; N]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalized<const N: usize>(mut v: [f64; N]) -> [f64; N] {
+    let norm = dot(&v, &v).sqrt();
+    for x in v.iter_mut() {
+        *x /= norm;
+    }
+    v
+}
+
+fn random_unit_vector<const N: usize, R: Rng>(rng: &mut R) -> [f64; N] {
+    // Rejection sampling from the unit ball, then projection onto the sphere.
+    loop {
+        let mut v = [0.0; N];
+        for x in v.iter_mut() {
+            *x = rng.random_range(-1.0..1.0);
+        }
+        let r2 = dot(&v, &v);
+        if r2 > 1e-6 && r2 <= 1.0 {
+            return normalized(v);
+        }
+    }
+}
+
+/// Wraps an angle difference into (-π, π].
+fn wrap_angle(mut a: f64) -> f64 {
+    while a > PI {
+        a -= 2.0 * PI;
+    }
+    while a <= -PI {
+        a += 2.0 * PI;
+    }
+    a
+}
+
+impl<const N: usize> VectorSpinModel<N> {
+    /// Square L×L lattice with periodic boundaries.
+    pub fn new(size: usize, temperature: f64) -> Self {
+        Self::with_lattice(Lattice::square(size, Boundary::Periodic), temperature)
+    }
+
+    pub fn with_lattice(lattice: Lattice, temperature: f64) -> Self {
+        assert!(N >= 2, "Vector spins need at least two components");
+        let mut rng = rand::rng();
+        let spins = (0..lattice.num_sites)
+            .map(|_| random_unit_vector(&mut rng))
+            .collect();
+        Self {
+            coupling: 1.0,
+            temperature,
+            step_size: 1.0,
+            lattice,
+            spins,
+        }
+    }
+
+    pub fn num_spins(&self) -> usize {
+        self.lattice.num_sites
+    }
+
+    /// Molecular field h_i = Σ_j sign_ij S_j acting on `site`.
+    pub fn local_field(&self, site: usize) -> [f64; N] {
+        let mut h = [0.0; N];
+        for n in &self.lattice.neighbors[site] {
+            for (hk, sk) in h.iter_mut().zip(&self.spins[n.site]) {
+                *hk += n.sign as f64 * sk;
+            }
+        }
+        h
+    }
+
+    /// N random-site Metropolis moves S → normalize(S + δ), |δ| ≤ `step_size`.
+    /// Returns the acceptance rate so `step_size` can be tuned.
+    pub fn metropolis_sweep(&mut self) -> f64 {
+        let mut rng = rand::rng();
+        let mut accepted = 0;
+        for _ in 0..self.num_spins() {
+            let site = rng.random_range(0..self.num_spins());
+            let old = self.spins[site];
+            let shift: [f64; N] = random_unit_vector(&mut rng);
+            let length = self.step_size * rng.random::<f64>();
+            let mut proposal = old;
+            for (p, d) in proposal.iter_mut().zip(&shift) {
+                *p += length * d;
+            }
+            let proposal = normalized(proposal);
+            let h = self.local_field(site);
+            let delta_e = -self.coupling * (dot(&proposal, &h) - dot(&old, &h));
+            if delta_e <= 0.0 || rng.random::<f64>() < (-delta_e / self.temperature).exp() {
+                self.spins[site] = proposal;
+                accepted += 1;
+            }
+        }
+        accepted as f64 / self.num_spins() as f64
+    }
+
+    /// Microcanonical over-relaxation sweep: every spin is reflected about its
+    /// local field, S → 2 (S·h) h / |h|² - S, which leaves the energy unchanged.
+    pub fn over_relaxation_sweep(&mut self) {
+        for site in 0..self.num_spins() {
+            let h = self.local_field(site);
+            let h2 = dot(&h, &h);
+            if h2 < 1e-12 {
+                continue;
+            }
+            let s = self.spins[site];
+            let factor = 2.0 * dot(&s, &h) / h2;
+            for k in 0..N {
+                self.spins[site][k] = factor * h[k] - s[k];
+            }
+        }
+    }
+
+    /// Embedded-cluster Wolff move: reflect spins about the plane normal to a
+    /// random direction r, growing the cluster with probability
+    /// 1 - exp(min(0, -2 J sign (r·S_i)(r·S_j) / T)). Returns the cluster size.
+    pub fn wolff_step(&mut self) -> usize {
+        let mut rng = rand::rng();
+        let r: [f64; N] = random_unit_vector(&mut rng);
+        let seed = rng.random_range(0..self.num_spins());
+        let mut in_cluster = vec![false; self.num_spins()];
+        in_cluster[seed] = true;
+        let mut stack = vec![seed];
+        let mut cluster_size = 1;
+        while let Some(site) = stack.pop() {
+            let proj_i = dot(&r, &self.spins[site]);
+            for n in &self.lattice.neighbors[site] {
+                if in_cluster[n.site] {
+                    continue;
+                }
+                let proj_j = dot(&r, &self.spins[n.site]);
+                let x = 2.0 * self.coupling * n.sign as f64 * proj_i * proj_j / self.temperature;
+                if x > 0.0 && rng.random::<f64>() < 1.0 - (-x).exp() {
+                    in_cluster[n.site] = true;
+                    stack.push(n.site);
+                    cluster_size += 1;
+                }
+            }
+        }
+        for (spin, &flip) in self.spins.iter_mut().zip(&in_cluster) {
+            if flip {
+                let proj = dot(&r, spin);
+                for (s, rk) in spin.iter_mut().zip(&r) {
+                    *s -= 2.0 * proj * rk;
+                }
+            }
+        }
+        cluster_size
+    }
+
+    pub fn energy(&self) -> f64 {
+        -self.coupling
+            * self
+                .lattice
+                .bonds
+                .iter()
+                .map(|b| b.sign as f64 * dot(&self.spins[b.a], &self.spins[b.b]))
+                .sum::<f64>()
+    }
+
+    pub fn energy_per_spin(&self) -> f64 {
+        self.energy() / self.num_spins() as f64
+    }
+
+    /// Magnetisation vector per spin.
+    pub fn magnetization(&self) -> [f64; N] {
+        let mut m = [0.0; N];
+        for s in &self.spins {
+            for (mk, sk) in m.iter_mut().zip(s) {
+                *mk += sk;
+            }
+        }
+        for mk in m.iter_mut() {
+            *mk /= self.num_spins() as f64;
+        }
+        m
+    }
+
+    fn angle(&self, site: usize) -> f64 {
+        self.spins[site][1].atan2(self.spins[site][0])
+    }
+
+    /// Single-configuration estimator of the XY helicity modulus along bond
+    /// direction 0: (1/N) [J Σ cos(θ_i - θ_j) - (J²/T) (Σ sin(θ_i - θ_j))²].
+    pub fn helicity_modulus_sample(&self) -> f64 {
+        assert_eq!(N, 2, "Helicity modulus is defined for the XY model");
+        let mut cos_sum = 0.0;
+        let mut sin_sum = 0.0;
+        for b in self.lattice.bonds.iter().filter(|b| b.direction == 0) {
+            let d = self.angle(b.b) - self.angle(b.a);
+            cos_sum += b.sign as f64 * d.cos();
+            sin_sum += b.sign as f64 * d.sin();
+        }
+        let j = self.coupling;
+        (j * cos_sum - j * j * sin_sum * sin_sum / self.temperature) / self.num_spins() as f64
+    }
+
+    /// Number of vortices plus antivortices per plaquette of a square lattice.
+    pub fn vortex_density(&self) -> f64 {
+        assert_eq!(N, 2, "Vortices are defined for the XY model");
+        assert_eq!(
+            self.lattice.kind,
+            LatticeKind::Square,
+            "Vortex counting needs a square lattice"
+        );
+        let l = self.lattice.dims[0];
+        let wrap = self.lattice.boundary != Boundary::Open;
+        let mut vortices = 0;
+        let mut plaquettes = 0;
+        for y in 0..l {
+            for x in 0..l {
+                if !wrap && (x + 1 == l || y + 1 == l) {
+                    continue;
+                }
+                let corners = [
+                    self.lattice.index(&[x, y]),
+                    self.lattice.index(&[(x + 1) % l, y]),
+                    self.lattice.index(&[(x + 1) % l, (y + 1) % l]),
+                    self.lattice.index(&[x, (y + 1) % l]),
+                ];
+                let winding: f64 = (0..4)
+                    .map(|k| wrap_angle(self.angle(corners[(k + 1) % 4]) - self.angle(corners[k])))
+                    .sum();
+                if (winding / (2.0 * PI)).round() != 0.0 {
+                    vortices += 1;
+                }
+                plaquettes += 1;
+            }
+        }
+        vortices as f64 / plaquettes as f64
+    }
+
+    /// Runs `thermalization` updates, then measures after each of `measurements`
+    /// further updates. Helicity modulus and vortex density are only recorded for XY spins.
+    pub fn measure_with<F>(
+        &mut self,
+        thermalization: usize,
+        measurements: usize,
+        mut update: F,
+    ) -> VectorSpinObservables
+    where
+        F: FnMut(&mut Self),
+    {
+        for _ in 0..thermalization {
+            update(self);
+        }
+        let xy_square = N == 2 && self.lattice.kind == LatticeKind::Square;
+        let mut energies = Vec::with_capacity(measurements);
+        let mut abs_m = Vec::with_capacity(measurements);
+        let mut m2 = Vec::with_capacity(measurements);
+        let mut helicity = Vec::new();
+        let mut vortices = Vec::new();
+        for _ in 0..measurements {
+            update(self);
+            energies.push(self.energy_per_spin());
+            let m = self.magnetization();
+            let norm2 = dot(&m, &m);
+            abs_m.push(norm2.sqrt());
+            m2.push(norm2);
+            if N == 2 {
+                helicity.push(self.helicity_modulus_sample());
+            }
+            if xy_square {
+                vortices.push(self.vortex_density());
+            }
+        }
+        let n = self.num_spins() as f64;
+        let t = self.temperature;
+        let blocks = jackknife_blocks(&[&abs_m]);
+        VectorSpinObservables {
+            energy: mean_with_error(&energies),
+            abs_magnetization: mean_with_error(&abs_m),
+            susceptibility: jackknife(&[&abs_m, &m2], blocks, |x| n * (x[1] - x[0] * x[0]) / t),
+            helicity_modulus: (N == 2).then(|| mean_with_error(&helicity)),
+            vortex_density: xy_square.then(|| mean_with_error(&vortices)),
+        }
+    }
+}
+
+pub fn xy_example() {
+    for t in [0.5, 0.9, 1.5] {
+        let mut model = XyModel::new(16, t);
+        let obs = model.measure_with(200, 500, |m| {
+            m.wolff_step();
+            m.metropolis_sweep();
+            m.over_relaxation_sweep();
+        });
+        let helicity = obs.helicity_modulus.unwrap();
+        let vortices = obs.vortex_density.unwrap();
+        println!(
+            "XY T = {}: helicity modulus = {:.4} ± {:.4}, vortex density = {:.4} ± {:.4}",
+            t, helicity.mean, helicity.error, vortices.mean, vortices.error
+        );
+    }
+}
+
+#[test]
+fn test_over_relaxation_conserves_energy() {
+    let mut model =
+        HeisenbergModel::with_lattice(Lattice::simple_cubic(4, Boundary::Periodic), 1.0);
+    model.metropolis_sweep();
+    let e0 = model.energy();
+    for _ in 0..10 {
+        model.over_relaxation_sweep();
+    }
+    assert!((model.energy() - e0).abs() < 1e-9);
+    assert!(model.spins.iter().all(|s| (dot(s, s) - 1.0).abs() < 1e-9));
+}
+
+#[test]
+fn test_xy_bkt_observables() {
+    // Well below T_BKT ≈ 0.89: stiff, vortex-free. Well above: Υ ≈ 0, many vortices.
+    let mut cold = XyModel::new(8, 0.4);
+    let obs = cold.measure_with(100, 300, |m| {
+        m.wolff_step();
+        m.metropolis_sweep();
+        m.over_relaxation_sweep();
+    });
+    assert!(obs.helicity_modulus.unwrap().mean > 0.7);
+    assert!(obs.vortex_density.unwrap().mean < 0.01);
+
+    let mut hot = XyModel::new(8, 2.0);
+    let obs = hot.measure_with(100, 300, |m| {
+        m.wolff_step();
+        m.metropolis_sweep();
+    });
+    assert!(obs.helicity_modulus.unwrap().mean < 0.2);
+    assert!(obs.vortex_density.unwrap().mean > 0.05);
+}
+
+#[test]
+fn test_heisenberg_updates_agree() {
+    let run = |wolff: bool| {
+        let mut model = HeisenbergModel::new(6, 1.0);
+        model.measure_with(200, 2000, |m| {
+            if wolff {
+                m.wolff_step();
+            }
+            m.metropolis_sweep();
+        })
+    };
+    let a = run(false).energy;
+    let b = run(true).energy;
+    let sigma = (a.error.powi(2) + b.error.powi(2)).sqrt();
+    assert!((a.mean - b.mean).abs() < 5.0 * sigma + 1e-3);
+}
@@ -0,0 +1,327 @@
+use crate::applications::percolation::Percolation;
+use crate::models::lattice::Lattice;
+use crate::physics::ising_model::IsingModel;
+
+/// Output formats understood by `Image::encode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Binary greyscale portable graymap (P5), luminance of the RGB pixels.
+    Pgm,
+    /// Binary portable pixmap (P6).
+    Ppm,
+    /// 8-bit RGB PNG with uncompressed (stored) deflate blocks.
+    Png,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Pgm => "pgm",
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+/// RGB raster image, row-major from the top-left pixel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0; 3]; width * height],
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: [u8; 3]) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    /// Draws the first two lattice axes (x along axis 0, y along axis 1), every site
+    /// as a `scale` × `scale` block; higher-dimensional lattices show the layer at
+    /// coordinate 0 of the remaining axes.
+    pub fn from_lattice<F>(lattice: &Lattice, scale: usize, color: F) -> Self
+    where
+        F: Fn(usize) -> [u8; 3],
+    {
+        assert!(scale >= 1, "Scale must be at least one pixel per site");
+        let width = lattice.dims[0];
+        let height = lattice.dims.get(1).copied().unwrap_or(1);
+        let mut image = Self::new(width * scale, height * scale);
+        // Axis 0 runs fastest, so the first width × height sites form the z = 0 layer.
+        for site in 0..width * height {
+            let (x, y) = (site % width, site / width);
+            let c = color(site);
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    image.set(x * scale + dx, y * scale + dy, c);
+                }
+            }
+        }
+        image
+    }
+
+    pub fn encode(&self, format: ImageFormat) -> Vec<u8> {
+        match format {
+            ImageFormat::Pgm => {
+                let mut out = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+                out.extend(self.pixels.iter().map(|&p| luminance(p)));
+                out
+            }
+            ImageFormat::Ppm => {
+                let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+                out.extend(self.pixels.iter().flatten());
+                out
+            }
+            ImageFormat::Png => self.encode_png(),
+        }
+    }
+
+    pub fn write(&self, path: &str, format: ImageFormat) -> std::io::Result<()> {
+        std::fs::write(path, self.encode(format))
+    }
+
+    /// PNG with a single IDAT chunk holding a zlib stream of stored deflate blocks;
+    /// every scanline uses filter type 0.
+    fn encode_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+
+        let mut zlib = vec![0x78, 0x01];
+        let blocks: Vec<&[u8]> = if raw.is_empty() {
+            vec![&[]]
+        } else {
+            raw.chunks(65_535).collect()
+        };
+        for (k, block) in blocks.iter().enumerate() {
+            zlib.push((k + 1 == blocks.len()) as u8);
+            let len = block.len() as u16;
+            zlib.extend(len.to_le_bytes());
+            zlib.extend((!len).to_le_bytes());
+            zlib.extend(*block);
+        }
+        zlib.extend(adler32(&raw).to_be_bytes());
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // Bit depth 8, colour type 2 (RGB), default compression, filter and interlace.
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut out = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        write_chunk(&mut out, b"IHDR", &header);
+        write_chunk(&mut out, b"IDAT", &zlib);
+        write_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+}
+
+fn luminance([r, g, b]: [u8; 3]) -> u8 {
+    ((299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000) as u8
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+/// CRC-32 (IEEE, reflected polynomial 0xEDB88320) as used by PNG chunks.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Adler-32 checksum closing a zlib stream.
+pub fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65_521;
+        b %= 65_521;
+    }
+    (b << 16) | a
+}
+
+/// Systems that can be drawn as an image of their lattice.
+pub trait Snapshot {
+    fn snapshot(&self, scale: usize) -> Image;
+}
+
+impl Snapshot for IsingModel {
+    /// Up spins white, down spins black.
+    fn snapshot(&self, scale: usize) -> Image {
+        Image::from_lattice(&self.lattice, scale, |site| {
+            if self.spins[site] > 0 {
+                [255; 3]
+            } else {
+                [0; 3]
+            }
+        })
+    }
+}
+
+impl Snapshot for Percolation {
+    /// Open sites white, blocked sites dark grey.
+    fn snapshot(&self, scale: usize) -> Image {
+        Image::from_lattice(&self.lattice, scale, |site| {
+            if self.sites[site] {
+                [255; 3]
+            } else {
+                [40; 3]
+            }
+        })
+    }
+}
+
+/// Writes `{directory}/{prefix}_{frame:05}.{ext}` every `interval` sweeps
+/// - Frame 0 is the initial configuration
+/// - The files can be assembled into a movie with any external tool, or viewed directly
+pub struct FrameWriter {
+    pub directory: String,
+    pub prefix: String,
+    pub interval: usize,
+    pub scale: usize,
+    pub format: ImageFormat,
+    pub frames_written: usize,
+}
+
+impl FrameWriter {
+    /// PNG frames at 4 pixels per site.
+    pub fn new(directory: &str, prefix: &str, interval: usize) -> Self {
+        assert!(interval >= 1, "Frame interval must be at least one sweep");
+        Self {
+            directory: directory.to_string(),
+            prefix: prefix.to_string(),
+            interval,
+            scale: 4,
+            format: ImageFormat::Png,
+            frames_written: 0,
+        }
+    }
+
+    /// Writes the next frame and returns its path.
+    pub fn write_frame<S: Snapshot>(&mut self, system: &S) -> std::io::Result<String> {
+        let path = format!(
+            "{}/{}_{:05}.{}",
+            self.directory,
+            self.prefix,
+            self.frames_written,
+            self.format.extension()
+        );
+        system.snapshot(self.scale).write(&path, self.format)?;
+        self.frames_written += 1;
+        Ok(path)
+    }
+
+    /// Applies `update` `sweeps` times, writing the initial state and one frame
+    /// after every `interval` sweeps; returns the paths written.
+    pub fn run<S, F>(
+        &mut self,
+        system: &mut S,
+        sweeps: usize,
+        mut update: F,
+    ) -> std::io::Result<Vec<String>>
+    where
+        S: Snapshot,
+        F: FnMut(&mut S),
+    {
+        std::fs::create_dir_all(&self.directory)?;
+        let mut paths = vec![self.write_frame(system)?];
+        for sweep in 1..=sweeps {
+            update(system);
+            if sweep % self.interval == 0 {
+                paths.push(self.write_frame(system)?);
+            }
+        }
+        Ok(paths)
+    }
+}
+
+pub fn visualization_example() {
+    // Encode in memory only; `FrameWriter` writes the same images to disk.
+    let mut model = IsingModel::new(16, 1.5);
+    for _ in 0..20 {
+        model.step();
+    }
+    let image = model.snapshot(4);
+    for format in [ImageFormat::Pgm, ImageFormat::Ppm, ImageFormat::Png] {
+        println!(
+            "{}×{} Ising snapshot as {}: {} bytes",
+            image.width,
+            image.height,
+            format.extension(),
+            image.encode(format).len()
+        );
+    }
+}
+
+#[test]
+fn test_checksums_and_encoders() {
+    assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+    assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+
+    let mut image = Image::new(3, 2);
+    image.set(1, 0, [255, 0, 0]);
+    let pgm = image.encode(ImageFormat::Pgm);
+    assert!(pgm.starts_with(b"P5\n3 2\n255\n"));
+    assert_eq!(pgm.len(), 11 + 6);
+    assert_eq!(pgm[12], 76);
+    let ppm = image.encode(ImageFormat::Ppm);
+    assert_eq!(&ppm[11 + 3..11 + 6], &[255, 0, 0]);
+
+    let png = image.encode(ImageFormat::Png);
+    assert_eq!(&png[..8], &[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a]);
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(
+        &png[png.len() - 12..],
+        &[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]
+    );
+}
+
+#[test]
+fn test_frame_writer() {
+    let directory = std::env::temp_dir().join(format!("frames_{}", std::process::id()));
+    let directory = directory.to_str().unwrap();
+    let mut model = IsingModel::new(8, 2.0);
+    let mut writer = FrameWriter::new(directory, "ising", 5);
+    let paths = writer.run(&mut model, 20, |m| m.step()).unwrap();
+    assert_eq!(paths.len(), 5);
+    let first = std::fs::read(&paths[0]).unwrap();
+    assert_eq!(&first[1..4], b"PNG");
+
+    let mut percolation = Percolation::new(6);
+    percolation.open(2, 3);
+    writer.format = ImageFormat::Pgm;
+    writer.scale = 1;
+    let path = writer.write_frame(&percolation).unwrap();
+    let pgm = std::fs::read(&path).unwrap();
+    assert_eq!(pgm[pgm.len() - 36 + 2 * 6 + 3], 255);
+    std::fs::remove_dir_all(directory).unwrap();
+}
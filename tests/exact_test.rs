@@ -0,0 +1,52 @@
+use monte_carlo::models::lattice::{Boundary, Lattice};
+use monte_carlo::physics::exact::{
+    onsager_energy, onsager_magnetization, ChainTransferMatrix, ExactEnumeration,
+};
+use monte_carlo::physics::hilbert::IsingHamiltonian;
+use monte_carlo::physics::ising_model::{IsingCouplings, IsingModel};
+
+#[test]
+fn test_ising_model_matches_enumeration() {
+    let exact = ExactEnumeration::new(&Lattice::square(4, Boundary::Periodic)).at(2.5);
+    let obs = IsingModel::new(4, 2.5).measure(1000, 20_000);
+    assert!((obs.energy.mean - exact.energy).abs() < 5.0 * obs.energy.error);
+    assert!(
+        (obs.abs_magnetization.mean - exact.abs_magnetization).abs()
+            < 5.0 * obs.abs_magnetization.error
+    );
+    assert!((obs.specific_heat.mean - exact.specific_heat).abs() < 5.0 * obs.specific_heat.error);
+}
+
+#[test]
+fn test_ising_model_matches_transfer_matrix() {
+    let exact = ChainTransferMatrix::new(&IsingHamiltonian { j: 1.0, h: 0.0 }, 16).at(1.0);
+    let lattice = Lattice::hypercubic(1, 16, Boundary::Open);
+    let obs = IsingModel::with_lattice(lattice, 1.0).measure(1000, 20_000);
+    assert!((obs.energy.mean - exact.energy).abs() < 5.0 * obs.energy.error);
+
+    // Field and antiferromagnetic coupling go through the same Hamiltonian.
+    let hamiltonian = IsingHamiltonian { j: -0.7, h: 0.4 };
+    let exact = ChainTransferMatrix::new(&hamiltonian, 16).at(1.0);
+    let lattice = Lattice::hypercubic(1, 16, Boundary::Open);
+    let couplings = IsingCouplings::from_hamiltonian(&lattice, &hamiltonian);
+    let obs = IsingModel::with_lattice(lattice, 1.0)
+        .with_couplings(couplings)
+        .measure(1000, 20_000);
+    assert!((obs.energy.mean - exact.energy).abs() < 5.0 * obs.energy.error);
+    assert!((obs.magnetization.mean - exact.magnetization).abs() < 5.0 * obs.magnetization.error);
+}
+
+#[test]
+fn test_ising_model_matches_onsager() {
+    // At T = 2.0 the correlation length is short, so L = 32 is already
+    // within a few 1e-3 of the infinite lattice.
+    let mut model = IsingModel::new(32, 2.0);
+    let obs = model.measure_with(200, 2000, |m| {
+        m.wolff_sweep();
+    });
+    assert!((obs.energy.mean - onsager_energy(2.0)).abs() < 5.0 * obs.energy.error + 0.005);
+    assert!(
+        (obs.abs_magnetization.mean - onsager_magnetization(2.0)).abs()
+            < 5.0 * obs.abs_magnetization.error + 0.005
+    );
+}